@@ -0,0 +1,537 @@
+extern crate proc_macro;
+
+use inflector::Inflector;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::Data::Enum;
+use syn::Data::Struct;
+use syn::Data::Union;
+use syn::Fields::*;
+use syn::Type::*;
+
+/// Derive `clingo::ToSymbol`.
+///
+/// Structs and enum variants are converted to functions whose name is the snake case
+/// version of the type or variant name, fields become the arguments of the function.
+///
+/// The conversion can be customized with `#[clingo(...)]` attributes:
+///
+/// * `#[clingo(name = "edge")]` on a struct or variant - use the given function name
+/// * `#[clingo(transparent)]` on a struct or variant with exactly one non skipped field -
+///   use the symbol of the field instead of wrapping it in a function
+/// * `#[clingo(skip)]` on a field - do not add the field to the arguments
+/// * `#[clingo(string)]` on a field - convert the field to a string symbol using its
+///   `Display` implementation
+#[proc_macro_derive(ToSymbol, attributes(clingo))]
+pub fn derive_fact(input: TokenStream) -> TokenStream {
+    // Construct a representation of Rust code as a syntax tree
+    // that we can manipulate
+    let ast = syn::parse(input).expect("heeh");
+
+    // Build the trait implementation
+    impl_fact(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `clingo::FromSymbol` with `type Error = ClingoError`.
+///
+/// The expected symbols are the ones produced by the `ToSymbol` derive and the same
+/// `#[clingo(...)]` attributes are supported. Skipped fields are initialized with
+/// `Default::default()` and string fields are parsed using their `FromStr` implementation.
+///
+/// The arity of a symbol has to match the number of non skipped fields, which can be stated
+/// explicitly with `#[clingo(arity_check)]`. Structs and variants annotated with
+/// `#[clingo(allow_extra_args)]` also accept symbols with additional arguments, which are
+/// ignored. Variants whose arity matches exactly are preferred, and if the arguments of a
+/// symbol cannot be converted, the next variant with the same name is tried.
+#[proc_macro_derive(FromSymbol, attributes(clingo))]
+pub fn derive_from_symbol(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).expect("heeh");
+
+    impl_from_symbol(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// `#[clingo(...)]` attributes of structs, enums and enum variants
+#[derive(Default)]
+struct TypeAttributes {
+    name: Option<String>,
+    transparent: bool,
+    arity_check: bool,
+    allow_extra_args: bool,
+}
+
+/// `#[clingo(...)]` attributes of fields
+#[derive(Default)]
+struct FieldAttributes {
+    skip: bool,
+    string: bool,
+}
+
+fn type_attributes(attrs: &[syn::Attribute]) -> syn::Result<TypeAttributes> {
+    let mut attributes = TypeAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("clingo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let name: syn::LitStr = meta.value()?.parse()?;
+                attributes.name = Some(name.value());
+            } else if meta.path.is_ident("transparent") {
+                attributes.transparent = true;
+            } else if meta.path.is_ident("arity_check") {
+                attributes.arity_check = true;
+            } else if meta.path.is_ident("allow_extra_args") {
+                attributes.allow_extra_args = true;
+            } else {
+                return Err(meta.error(
+                    "unsupported clingo attribute, expected `name`, `transparent`, `arity_check` or `allow_extra_args`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attributes)
+}
+
+fn field_attributes(attrs: &[syn::Attribute]) -> syn::Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("clingo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                attributes.skip = true;
+            } else if meta.path.is_ident("string") {
+                attributes.string = true;
+            } else {
+                return Err(meta
+                    .error("unsupported clingo attribute on field, expected `skip` or `string`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// Reject attributes on enums that are only meaningful on structs and variants.
+fn check_enum_attributes(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let attributes = type_attributes(&ast.attrs)?;
+    if attributes.name.is_some()
+        || attributes.transparent
+        || attributes.arity_check
+        || attributes.allow_extra_args
+    {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "clingo attributes on enums have to be put on the variants",
+        ));
+    }
+    Ok(())
+}
+
+/// A struct or enum variant with its attributes and the bindings of its fields
+struct Shape<'a> {
+    /// path used to construct and match the value, e.g. `Self` or `Self::Variant`
+    path: proc_macro2::TokenStream,
+    /// function name of the symbol
+    predicate_name: String,
+    attributes: TypeAttributes,
+    fields: &'a syn::Fields,
+    field_attributes: Vec<FieldAttributes>,
+}
+
+impl<'a> Shape<'a> {
+    fn new(
+        path: proc_macro2::TokenStream,
+        ident: &syn::Ident,
+        attrs: &[syn::Attribute],
+        fields: &'a syn::Fields,
+    ) -> syn::Result<Shape<'a>> {
+        let attributes = type_attributes(attrs)?;
+        let predicate_name = attributes
+            .name
+            .clone()
+            .unwrap_or_else(|| ident.to_string().to_snake_case());
+        let field_attributes = fields
+            .iter()
+            .map(|field| field_attributes(&field.attrs))
+            .collect::<syn::Result<Vec<_>>>()?;
+        let shape = Shape {
+            path,
+            predicate_name,
+            attributes,
+            fields,
+            field_attributes,
+        };
+        if shape.attributes.transparent && shape.arguments().len() != 1 {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "transparent requires exactly one field that is not skipped",
+            ));
+        }
+        if shape.attributes.transparent && shape.attributes.name.is_some() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "transparent cannot be combined with name",
+            ));
+        }
+        if shape.attributes.allow_extra_args
+            && (shape.attributes.transparent || shape.attributes.arity_check)
+        {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "allow_extra_args cannot be combined with transparent or arity_check",
+            ));
+        }
+        for (field, attributes) in fields.iter().zip(&shape.field_attributes) {
+            if attributes.skip && attributes.string {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "skip cannot be combined with string",
+                ));
+            }
+        }
+        Ok(shape)
+    }
+
+    /// The binding of the field at `index` in patterns
+    fn binding(index: usize) -> syn::Ident {
+        syn::Ident::new(&format!("x{}", index + 1), Span::call_site())
+    }
+
+    /// Pattern that binds all fields that are not skipped
+    fn pattern(&self) -> proc_macro2::TokenStream {
+        let path = &self.path;
+        let bindings = self.fields.iter().enumerate().map(|(index, field)| {
+            let binding = if self.field_attributes[index].skip {
+                quote! {_}
+            } else {
+                let binding = Shape::binding(index);
+                quote! {#binding}
+            };
+            match &field.ident {
+                Some(ident) => quote! {#ident: #binding},
+                None => binding,
+            }
+        });
+        match self.fields {
+            Named(_) => quote! {#path{#(#bindings),*}},
+            Unnamed(_) => quote! {#path(#(#bindings),*)},
+            Unit => quote! {#path},
+        }
+    }
+
+    /// Fields that are not skipped together with their index
+    fn arguments(&self) -> Vec<(usize, &syn::Field, &FieldAttributes)> {
+        self.fields
+            .iter()
+            .zip(&self.field_attributes)
+            .enumerate()
+            .filter(|(_, (_, attributes))| !attributes.skip)
+            .map(|(index, (field, attributes))| (index, field, attributes))
+            .collect()
+    }
+
+    /// Expression converting the binding of a field to a symbol
+    fn to_symbol(
+        index: usize,
+        field: &syn::Field,
+        attributes: &FieldAttributes,
+    ) -> syn::Result<proc_macro2::TokenStream> {
+        let binding = Shape::binding(index);
+        if attributes.string {
+            return Ok(quote! {Symbol::create_string(&#binding.to_string())?});
+        }
+        check_type(&field.ty)?;
+        Ok(quote! {#binding.symbol()?})
+    }
+
+    fn match_arm_to_symbol(&self) -> syn::Result<proc_macro2::TokenStream> {
+        let pattern = self.pattern();
+        let mut arguments = vec![];
+        for (index, field, attributes) in self.arguments() {
+            arguments.push(Shape::to_symbol(index, field, attributes)?);
+        }
+        if self.attributes.transparent {
+            let argument = &arguments[0];
+            return Ok(quote! {
+                #pattern => Ok(#argument),
+            });
+        }
+        let predicate_name = &self.predicate_name;
+        if let Unit = self.fields {
+            return Ok(quote! {
+                #pattern => Symbol::create_id(#predicate_name, true),
+            });
+        }
+        Ok(quote! {
+            #pattern => Symbol::create_function(#predicate_name, &[#(#arguments),*], true),
+        })
+    }
+
+    /// Expression constructing the value of a field from `symbol`
+    ///
+    /// Conversion errors are returned if `fallible` is false. Otherwise, the first error is
+    /// stored in `error` and the block labeled `'candidate` is left, so that the next shape can
+    /// be tried.
+    fn from_symbol(
+        field: &syn::Field,
+        attributes: &FieldAttributes,
+        symbol: proc_macro2::TokenStream,
+        error_msg: &str,
+        fallible: bool,
+    ) -> proc_macro2::TokenStream {
+        let ty = &field.ty;
+        let value = if attributes.string {
+            quote! {
+                #symbol.string().and_then(|string| {
+                    <#ty as ::std::str::FromStr>::from_str(string)
+                        .map_err(|_| ClingoError::new_external(#error_msg))
+                })
+            }
+        } else {
+            quote! {<#ty as FromSymbol>::from_symbol(#symbol).map_err(ClingoError::from)}
+        };
+        if !fallible {
+            return quote! {#value?};
+        }
+        quote! {
+            match #value {
+                Ok(value) => value,
+                Err(err) => {
+                    if error.is_none() {
+                        error = Some(err);
+                    }
+                    break 'candidate;
+                }
+            }
+        }
+    }
+
+    /// Constructor expression of the value, where the non skipped fields are taken from
+    /// `symbol` if transparent, and from `arguments` otherwise
+    ///
+    /// See [`Shape::from_symbol()`] for `fallible`.
+    fn constructor(&self, error_msg: &str, fallible: bool) -> proc_macro2::TokenStream {
+        self.constructor_with(|position, field, attributes| {
+            let symbol = if self.attributes.transparent {
+                quote! {symbol}
+            } else {
+                quote! {arguments[#position]}
+            };
+            Shape::from_symbol(field, attributes, symbol, error_msg, fallible)
+        })
+    }
+
+    /// Constructor expression of the value, where the non skipped fields are given by `value`
+    fn constructor_with<F>(&self, value: F) -> proc_macro2::TokenStream
+    where
+        F: Fn(usize, &syn::Field, &FieldAttributes) -> proc_macro2::TokenStream,
+    {
+        let path = &self.path;
+        let mut position = 0;
+        let mut values = vec![];
+        for (field, attributes) in self.fields.iter().zip(&self.field_attributes) {
+            let value = if attributes.skip {
+                quote! {::std::default::Default::default()}
+            } else {
+                position += 1;
+                value(position - 1, field, attributes)
+            };
+            values.push(match &field.ident {
+                Some(ident) => quote! {#ident: #value},
+                None => value,
+            });
+        }
+        match self.fields {
+            Named(_) => quote! {#path{#(#values),*}},
+            Unnamed(_) => quote! {#path(#(#values),*)},
+            Unit => quote! {#path},
+        }
+    }
+
+    /// Check whether `symbol` matches and return the constructed value on success.
+    ///
+    /// If `exact` is false, symbols with additional arguments match, too.
+    fn match_from_symbol(&self, error_msg: &str, exact: bool) -> proc_macro2::TokenStream {
+        if self.attributes.transparent {
+            // conversion errors are ignored, so that the next variant can be tried
+            let (_, field, attributes) = self.arguments()[0];
+            let ty = &field.ty;
+            let (pattern, value) = if attributes.string {
+                (
+                    quote! {Some(value)},
+                    quote! {
+                        symbol
+                            .string()
+                            .ok()
+                            .and_then(|string| <#ty as ::std::str::FromStr>::from_str(string).ok())
+                    },
+                )
+            } else {
+                (
+                    quote! {Ok(value)},
+                    quote! {<#ty as FromSymbol>::from_symbol(symbol)},
+                )
+            };
+            let constructor = self.constructor_with(|_, _, _| quote! {value});
+            return quote! {
+                if let #pattern = #value {
+                    return Ok(#constructor);
+                }
+            };
+        }
+        let constructor = self.constructor(error_msg, true);
+        let predicate_name = &self.predicate_name;
+        let arity = self.arguments().len();
+        let arity_check = if exact {
+            quote! {arguments.len() == #arity}
+        } else {
+            quote! {arguments.len() >= #arity}
+        };
+        let check = quote! {
+            if name == Some(#predicate_name) && #arity_check && symbol.is_positive()? {
+                return Ok(#constructor);
+            }
+        };
+        if arity == 0 {
+            return check;
+        }
+        quote! {
+            'candidate: {
+                #check
+            }
+        }
+    }
+}
+
+/// Integers with more than 32 bits cannot be represented as clingo numbers.
+fn check_type(ty: &syn::Type) -> syn::Result<()> {
+    match ty {
+        Path(type_path) => {
+            let typename = type_path.path.segments[0].ident.to_string();
+            match typename.as_ref() {
+                "u64" | "i64" | "u128" | "i128" => Err(syn::Error::new_spanned(
+                    ty,
+                    "Cannot derive_fact clingo library only support 32bit integers.",
+                )),
+                _ => Ok(()),
+            }
+        }
+        Reference(type_reference) => check_type(&type_reference.elem),
+        _ => Ok(()),
+    }
+}
+
+fn shapes(ast: &syn::DeriveInput) -> syn::Result<Vec<Shape<'_>>> {
+    match &ast.data {
+        Struct(data) => Ok(vec![Shape::new(
+            quote! {Self},
+            &ast.ident,
+            &ast.attrs,
+            &data.fields,
+        )?]),
+        Enum(data) => {
+            check_enum_attributes(ast)?;
+            data.variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    Shape::new(
+                        quote! {Self::#ident},
+                        ident,
+                        &variant.attrs,
+                        &variant.fields,
+                    )
+                })
+                .collect()
+        }
+        Union(_) => Err(syn::Error::new_spanned(
+            &ast.ident,
+            "Cannot derive ToSymbol or FromSymbol for Unions!",
+        )),
+    }
+}
+
+fn impl_fact(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let mut arms = vec![];
+    for shape in shapes(ast)? {
+        arms.push(shape.match_arm_to_symbol()?);
+    }
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ToSymbol for #name #ty_generics #where_clause {
+            fn symbol(&self) -> Result<Symbol, ClingoError> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+fn impl_from_symbol(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let error_msg = format!("Could not convert to {}", name);
+    let shapes = shapes(ast)?;
+    let body = match (&ast.data, shapes.as_slice()) {
+        // a transparent struct forwards the conversion errors of its field
+        (Struct(_), [shape]) if shape.attributes.transparent => {
+            let constructor = shape.constructor(&error_msg, false);
+            quote! {
+                Ok(#constructor)
+            }
+        }
+        _ => {
+            // symbols with matching arity are preferred over symbols with additional arguments
+            let exact = shapes
+                .iter()
+                .map(|shape| shape.match_from_symbol(&error_msg, true));
+            let prefix = shapes
+                .iter()
+                .filter(|shape| shape.attributes.allow_extra_args)
+                .map(|shape| shape.match_from_symbol(&error_msg, false));
+            let matches = exact.chain(prefix);
+            // name and arguments are only needed if there is a shape that is not transparent
+            let prelude = if shapes.iter().any(|shape| !shape.attributes.transparent) {
+                quote! {
+                    let name = symbol.name().ok();
+                    let arguments = symbol.arguments().unwrap_or_default();
+                }
+            } else {
+                quote! {}
+            };
+            // the first conversion error of an argument is reported if no shape matches
+            let (error, result) = if shapes
+                .iter()
+                .any(|shape| !shape.attributes.transparent && !shape.arguments().is_empty())
+            {
+                (
+                    quote! {let mut error: Option<ClingoError> = None;},
+                    quote! {Err(error.unwrap_or_else(|| ClingoError::new_external(#error_msg)))},
+                )
+            } else {
+                (
+                    quote! {},
+                    quote! {Err(ClingoError::new_external(#error_msg))},
+                )
+            };
+            quote! {
+                #prelude
+                #error
+                #(#matches)*
+                #result
+            }
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = &ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics FromSymbol for #name #ty_generics #where_clause {
+            type Error = ClingoError;
+            fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+                #body
+            }
+        }
+    })
+}
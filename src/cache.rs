@@ -0,0 +1,202 @@
+use crate::observer::{GroundProgram, GroundProgramRecorder, GroundStatement};
+use crate::{ClingoError, ControlCtx, FactBase, GenericControl, Part, Symbol};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::sync::{Arc, Mutex};
+
+/// An input of a grounding request that is part of the cache key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Input {
+    Program {
+        name: String,
+        parameters: Vec<String>,
+        program: String,
+    },
+    Facts(Vec<Symbol>),
+}
+
+/// The key of a cached ground program.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    arguments: Vec<String>,
+    inputs: Vec<Input>,
+    parts: Vec<(String, Vec<Symbol>)>,
+}
+
+/// A cache of ground programs keyed on control arguments, program texts, facts and grounded
+/// parts.
+///
+/// The cache can be shared between threads, e.g., to answer repeated requests in a service
+/// without regrounding identical inputs.
+///
+/// ```ignore
+/// let cache = GroundingCache::new();
+/// let arguments = vec!["-c".to_owned(), "n=3".to_owned()];
+/// let mut ctl = control(arguments.clone())?;
+/// let mut cached = cache.control(&mut ctl, &arguments);
+/// cached.add("base", &[], "p(X) :- q(X).");
+/// cached.add_facts(&facts);
+/// let hit = cached.ground(&[Part::new("base", vec![])?])?;
+/// ```
+#[derive(Debug, Default)]
+pub struct GroundingCache {
+    entries: Mutex<HashMap<Key, Arc<GroundProgram>>>,
+}
+impl GroundingCache {
+    /// Create an empty cache.
+    pub fn new() -> GroundingCache {
+        GroundingCache::default()
+    }
+    /// The number of cached ground programs.
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Remove all cached ground programs.
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+    /// Collect a grounding request for the given control object.
+    ///
+    /// The control object must not have been grounded before, because a cached ground
+    /// program does not refer to atoms of earlier steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctl` - the control object
+    /// * `arguments` - the arguments the control object was created with, because options like
+    ///   `-c n=3` change the ground program
+    pub fn control<'c, C: ControlCtx>(
+        &'c self,
+        ctl: &'c mut GenericControl<C>,
+        arguments: &[String],
+    ) -> CachedControl<'c, C> {
+        CachedControl {
+            cache: self,
+            ctl,
+            arguments: arguments.to_vec(),
+            inputs: vec![],
+        }
+    }
+    fn get(&self, key: &Key) -> Option<Arc<GroundProgram>> {
+        self.entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.get(key).cloned())
+    }
+    fn insert(&self, key: Key, program: GroundProgram) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, Arc::new(program));
+        }
+    }
+}
+
+/// A grounding request collecting the inputs of [`GenericControl::add()`],
+/// [`GenericControl::add_facts()`] and [`GenericControl::ground()`].
+///
+/// The inputs are only passed to the control object if the ground program is not cached.
+/// Otherwise, the cached ground program is added via the [`Backend`](crate::Backend) as
+/// described in [`GroundProgramRecorder::replay()`].
+///
+/// Ground programs with shown terms, e.g., from `#show t : c.`, are not cached, because they
+/// cannot be added via the backend.
+///
+/// **Note:** On a cache miss, a [`GroundProgramRecorder`] is registered with the control
+/// object and disabled after grounding, because observers cannot be unregistered.
+#[derive(Debug)]
+pub struct CachedControl<'c, C: ControlCtx> {
+    cache: &'c GroundingCache,
+    ctl: &'c mut GenericControl<C>,
+    arguments: Vec<String>,
+    inputs: Vec<Input>,
+}
+impl<'c, C: ControlCtx> CachedControl<'c, C> {
+    /// Add a non-ground program, see [`GenericControl::add()`].
+    pub fn add(&mut self, name: &str, parameters: &[&str], program: &str) {
+        self.inputs.push(Input::Program {
+            name: name.to_owned(),
+            parameters: parameters.iter().map(|&p| p.to_owned()).collect(),
+            program: program.to_owned(),
+        });
+    }
+    /// Add facts, see [`GenericControl::add_facts()`].
+    pub fn add_facts(&mut self, facts: &FactBase) {
+        let mut symbols: Vec<Symbol> = facts.iter().copied().collect();
+        symbols.sort_by_key(|Symbol(symbol)| *symbol);
+        self.inputs.push(Input::Facts(symbols));
+    }
+    /// Ground the given parts or restore their cached ground program.
+    ///
+    /// **Returns** whether the ground program was restored from the cache
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the control object has already been grounded
+    /// - [`ClingoError`] if adding the inputs, grounding or restoring the ground program failed
+    pub fn ground(self, parts: &[Part]) -> Result<bool, ClingoError> {
+        if self.ctl.symbolic_atoms()?.size()? > 0 {
+            return Err(ClingoError::new_external(
+                "Control object has already been grounded",
+            ));
+        }
+        let mut key = Key {
+            arguments: self.arguments,
+            inputs: self.inputs,
+            parts: vec![],
+        };
+        for part in parts {
+            let name = unsafe { CStr::from_ptr(part.part.name) }.to_str()?;
+            key.parts.push((name.to_owned(), part._params.clone()));
+        }
+        if let Some(program) = self.cache.get(&key) {
+            program.replay(&mut self.ctl.backend()?)?;
+            return Ok(true);
+        }
+        for input in &key.inputs {
+            match input {
+                Input::Program {
+                    name,
+                    parameters,
+                    program,
+                } => {
+                    let parameters: Vec<&str> = parameters.iter().map(String::as_str).collect();
+                    self.ctl.add(name, &parameters, program)?;
+                }
+                Input::Facts(symbols) => {
+                    let mut facts = FactBase::new();
+                    for symbol in symbols {
+                        facts.insert(symbol)?;
+                    }
+                    self.ctl.add_facts(&facts)?;
+                }
+            }
+        }
+        let recorder = GroundProgramRecorder::new();
+        self.ctl
+            .register_observer(Box::new(recorder.clone()), false)?;
+        let result = self.ctl.ground(parts);
+        recorder.set_enabled(false);
+        result?;
+        let statements: Vec<GroundStatement> = recorder
+            .steps()
+            .into_iter()
+            .flat_map(|step| step.statements)
+            .collect();
+        recorder.clear();
+        let output_terms = statements
+            .iter()
+            .any(|statement| matches!(statement, GroundStatement::OutputTerm { .. }));
+        if !output_terms {
+            self.cache.insert(key, GroundProgram { statements });
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    ClingoError, ControlCtx, GenericControl, Part, Signature, SolveEventHandler, SolveMode,
+    SolveResult, SolverLiteral, Symbol, TruthValue,
+};
+
+/// The criterion to stop incremental solving, see [`IncrementalSolver::istop()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopCriterion {
+    /// Stop once a step is satisfiable (`SAT`).
+    Satisfiable,
+    /// Stop once a step is unsatisfiable (`UNSAT`).
+    Unsatisfiable,
+    /// Stop once a step is neither satisfiable nor unsatisfiable (`UNKNOWN`).
+    Unknown,
+}
+impl StopCriterion {
+    fn holds(self, result: &SolveResult) -> bool {
+        match self {
+            StopCriterion::Satisfiable => result.contains(SolveResult::SATISFIABLE),
+            StopCriterion::Unsatisfiable => result.contains(SolveResult::UNSATISFIABLE),
+            StopCriterion::Unknown => {
+                !result.intersects(SolveResult::SATISFIABLE | SolveResult::UNSATISFIABLE)
+            }
+        }
+    }
+}
+
+/// The outcome of [`IncrementalSolver::solve()`].
+#[derive(Debug)]
+pub struct IncrementalResult<C: ControlCtx> {
+    /// The control object after the last step.
+    pub control: GenericControl<C>,
+    /// The number of solved steps.
+    pub steps: u32,
+    /// The result of the last step or an empty result if no step was solved.
+    pub result: SolveResult,
+}
+
+/// A driver for incremental solving mirroring clingo's incremental mode.
+///
+/// In step `0`, the base program and `check(0)` are grounded, and in each step `t > 0`, the
+/// programs `step(t)` and `check(t)` are grounded.
+/// Before solving step `t`, the external atom `query(t)` is assigned true and the query atom of
+/// the previous step is released.
+///
+/// ```text
+/// #program base.
+/// pos(0).
+/// #program step(t).
+/// pos(t) :- pos(t-1).
+/// #program check(t).
+/// #external query(t).
+/// :- query(t), t < 3.
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalSolver {
+    imin: u32,
+    imax: Option<u32>,
+    istop: StopCriterion,
+    base: String,
+    step: String,
+    check: String,
+}
+impl Default for IncrementalSolver {
+    fn default() -> Self {
+        IncrementalSolver {
+            imin: 0,
+            imax: None,
+            istop: StopCriterion::Satisfiable,
+            base: "base".to_owned(),
+            step: "step".to_owned(),
+            check: "check".to_owned(),
+        }
+    }
+}
+impl IncrementalSolver {
+    /// Create a driver with the defaults of clingo's incremental mode.
+    ///
+    /// Solving stops at the first satisfiable step without bounds on the number of steps.
+    pub fn new() -> IncrementalSolver {
+        IncrementalSolver::default()
+    }
+    /// Set the minimum number of steps to solve.
+    pub fn imin(mut self, imin: u32) -> IncrementalSolver {
+        self.imin = imin;
+        self
+    }
+    /// Set the maximum number of steps to solve.
+    pub fn imax(mut self, imax: Option<u32>) -> IncrementalSolver {
+        self.imax = imax;
+        self
+    }
+    /// Set the criterion to stop solving.
+    pub fn istop(mut self, istop: StopCriterion) -> IncrementalSolver {
+        self.istop = istop;
+        self
+    }
+    /// Set the name of the base program, `base` by default.
+    pub fn base(mut self, name: &str) -> IncrementalSolver {
+        self.base = name.to_owned();
+        self
+    }
+    /// Set the name of the step program, `step` by default.
+    pub fn step(mut self, name: &str) -> IncrementalSolver {
+        self.step = name.to_owned();
+        self
+    }
+    /// Set the name of the check program, `check` by default.
+    pub fn check(mut self, name: &str) -> IncrementalSolver {
+        self.check = name.to_owned();
+        self
+    }
+    /// Ground and solve steps until the stop criterion holds or the maximum number of steps is
+    /// reached.
+    ///
+    /// The programs have to be added to the control object beforehand.
+    /// The event handler is called for the solve events of all steps.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError`] if grounding or solving a step failed
+    pub fn solve<C, H>(
+        &self,
+        mut ctl: GenericControl<C>,
+        handler: &mut H,
+    ) -> Result<IncrementalResult<C>, ClingoError>
+    where
+        C: ControlCtx,
+        H: SolveEventHandler,
+    {
+        let mut step = 0;
+        let mut result = None;
+        while step < self.imax.unwrap_or(u32::MAX)
+            && (step < self.imin.max(1)
+                || !result
+                    .as_ref()
+                    .is_some_and(|result| self.istop.holds(result)))
+        {
+            let t = Symbol::create_number(step as i32);
+            let mut parts = vec![Part::new(&self.check, vec![t])?];
+            if step > 0 {
+                if let Some(query) = query_literal(&ctl, step - 1)? {
+                    ctl.release_external(query)?;
+                }
+                parts.push(Part::new(&self.step, vec![t])?);
+            } else {
+                parts.push(Part::new(&self.base, vec![])?);
+            }
+            ctl.ground(&parts)?;
+            if let Some(query) = query_literal(&ctl, step)? {
+                ctl.assign_external(query, TruthValue::True)?;
+            }
+            let mut handle =
+                ctl.solve_with_event_handler(SolveMode::empty(), &[], &mut *handler)?;
+            result = Some(handle.get()?);
+            ctl = handle.close()?;
+            step += 1;
+        }
+        Ok(IncrementalResult {
+            control: ctl,
+            steps: step,
+            result: result.unwrap_or(SolveResult::empty()),
+        })
+    }
+}
+
+/// Get the literal of the external atom `query(step)` if it exists.
+fn query_literal<C: ControlCtx>(
+    ctl: &GenericControl<C>,
+    step: u32,
+) -> Result<Option<SolverLiteral>, ClingoError> {
+    let query = Symbol::create_function("query", &[Symbol::create_number(step as i32)], true)?;
+    let atoms = ctl.symbolic_atoms()?;
+    for atom in atoms.iter_with_signature(Signature::new("query", 1, true)?)? {
+        if atom.symbol()? == query {
+            return Ok(Some(atom.literal()?));
+        }
+    }
+    Ok(None)
+}
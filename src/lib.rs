@@ -4,19 +4,21 @@
 #![allow(clippy::try_err)]
 use bitflags::bitflags;
 use clingo_sys::*;
+use std::any::Any;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::convert::Infallible;
 use std::convert::TryInto;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::ffi::NulError;
 use std::fmt;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::os::raw::c_char;
 use std::os::raw::c_void;
 use std::ptr::NonNull;
 use std::str::Utf8Error;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use thiserror::Error;
 
@@ -24,6 +26,14 @@ use thiserror::Error;
 pub mod ast;
 mod ast_internals;
 
+/// Caching of ground programs.
+pub mod cache;
+/// Incremental solving in the style of clingo's incremental mode.
+pub mod incremental;
+/// Reusable ground program observers.
+pub mod observer;
+/// Reusable building blocks to implement propagators.
+pub mod propagator;
 pub mod theory;
 
 /// ClingoError in the rust wrapper, like null pointers or failed matches of C enums.
@@ -49,6 +59,14 @@ pub enum ClingoError {
     },
     #[error("ExternalError: ")]
     ExternalError(#[from] ExternalError),
+    #[error("DecodeError: could not decode symbol {symbol}: {source}")]
+    DecodeError {
+        symbol: String,
+        source: Box<ClingoError>,
+    },
+    #[cfg(feature = "dynamic")]
+    #[error("LibraryError: {0}")]
+    LibraryError(#[from] libloading::Error),
 }
 impl ClingoError {
     fn new_internal(msg: &'static str) -> ClingoError {
@@ -59,10 +77,18 @@ impl ClingoError {
         }
     }
 
-    fn new_external(msg: &'static str) -> ClingoError {
+    /// Create a [`ClingoError::ExternalError`] with the given message.
+    ///
+    /// Used by user code and derived [`FromSymbol`] implementations to report conversion errors.
+    pub fn new_external(msg: &'static str) -> ClingoError {
         ExternalError { msg }.into()
     }
 }
+impl From<Infallible> for ClingoError {
+    fn from(error: Infallible) -> Self {
+        match error {}
+    }
+}
 #[derive(Error, Debug)]
 #[error("ExternalError: {msg}")]
 pub struct ExternalError {
@@ -155,6 +181,12 @@ pub fn set_error(code: ErrorType, message: &str) -> Result<(), NulError> {
     Ok(())
 }
 
+/// Report an error of a callback as a runtime error in the active thread.
+pub(crate) fn report_error(error: &ClingoError) {
+    // a nul byte in the message only loses the message
+    let _ = set_error(ErrorType::Runtime, &error.to_string());
+}
+
 fn set_internal_error(code: ErrorType, message: &'static str) {
     // unwrap won't panic, because the function is only used internally on valid strings
     let message = CString::new(message).unwrap();
@@ -455,6 +487,17 @@ impl HeuristicType {
     }
 }
 
+/// Enumeration of theory sequence types.
+#[derive(Debug, Copy, Clone)]
+pub enum TheorySequenceType {
+    /// A tuple sequence, e.g., `(1,2,3)`
+    Tuple = clingo_theory_sequence_type_e_clingo_theory_sequence_type_tuple as isize,
+    /// A list sequence, e.g., `[1,2,3]`
+    List = clingo_theory_sequence_type_e_clingo_theory_sequence_type_list as isize,
+    /// A set sequence, e.g., `{1,2,3}`
+    Set = clingo_theory_sequence_type_e_clingo_theory_sequence_type_set as isize,
+}
+
 /// Enumeration of theory term types.
 #[derive(Debug, Copy, Clone)]
 pub enum TheoryTermType {
@@ -667,6 +710,11 @@ pub trait SolveEventHandler {
         true
     }
 }
+impl<T: SolveEventHandler + ?Sized> SolveEventHandler for &mut T {
+    fn on_solve_event(&mut self, event: SolveEvent, goon: &mut bool) -> bool {
+        (**self).on_solve_event(event, goon)
+    }
+}
 unsafe extern "C" fn unsafe_solve_callback<T: SolveEventHandler>(
     event_type: clingo_solve_event_type_t,
     event_data: *mut c_void,
@@ -867,7 +915,7 @@ unsafe extern "C" fn unsafe_ground_callback<T: FunctionHandler>(
     }
     let location = &*(location as *const ast::Location);
     let name = CStr::from_ptr(name);
-    let arguments = std::slice::from_raw_parts(arguments as *const Symbol, arguments_size);
+    let arguments = raw_slice(arguments as *const Symbol, arguments_size);
     let event_handler = &mut *(event_handler as *mut T);
 
     match try_symbol_callback(
@@ -967,6 +1015,10 @@ impl fmt::Display for Id {
 #[derive(Debug, Copy, Clone)]
 pub struct WeightedLiteral(clingo_weighted_literal);
 impl WeightedLiteral {
+    /// Create a literal with the given weight.
+    pub fn new(SolverLiteral(literal): SolverLiteral, weight: i32) -> WeightedLiteral {
+        WeightedLiteral(clingo_weighted_literal { literal, weight })
+    }
     pub fn literal(self) -> SolverLiteral {
         SolverLiteral(self.0.literal)
     }
@@ -1360,6 +1412,18 @@ impl Symbol {
     pub fn symbol_type(self) -> Result<SymbolType, ClingoError> {
         SymbolType::try_from(unsafe { clingo_symbol_type(self.0) } as u32)
     }
+
+    /// Get the signature of a function symbol.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::Runtime`] if symbol is not of type [`SymbolType::Function`]
+    /// - [`ClingoError::Utf8Error`]
+    pub fn signature(self) -> Result<Signature, ClingoError> {
+        let name = self.name()?;
+        let arity = self.arguments()?.len() as u32;
+        Signature::new(name, arity, self.is_positive()?)
+    }
 }
 
 /// Obtain the clingo version.
@@ -1553,6 +1617,14 @@ pub trait Propagator {
         true
     }
 }
+/// Create a slice from a pointer and a size, allowing a null pointer if the size is zero.
+unsafe fn raw_slice<'a, T>(data: *const T, size: usize) -> &'a [T] {
+    if size == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, size)
+    }
+}
 unsafe extern "C" fn unsafe_init<T: Propagator>(
     init: *mut clingo_propagate_init_t,
     propagator: *mut c_void,
@@ -1579,7 +1651,7 @@ unsafe extern "C" fn unsafe_propagate<T: Propagator>(
         return false;
     }
     let control = &mut *(control as *mut PropagateControl);
-    let changes = std::slice::from_raw_parts(changes as *const SolverLiteral, size);
+    let changes = raw_slice(changes as *const SolverLiteral, size);
     let propagator = &mut *(propagator as *mut T);
 
     propagator.propagate(control, changes)
@@ -1596,7 +1668,7 @@ unsafe extern "C" fn unsafe_undo<T: Propagator>(
         return;
     }
     let control = &mut *(control as *mut PropagateControl);
-    let changes = std::slice::from_raw_parts(changes as *const SolverLiteral, size);
+    let changes = raw_slice(changes as *const SolverLiteral, size);
     let propagator = &mut *(propagator as *mut T);
 
     propagator.undo(control, changes)
@@ -1634,6 +1706,60 @@ unsafe extern "C" fn unsafe_decide<T: Propagator>(
 
     propagator.decide(Id(thread_id), assignment, fallback, decision)
 }
+impl<P: Propagator + ?Sized> Propagator for Box<P> {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        (**self).init(init)
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        (**self).propagate(control, changes)
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        (**self).undo(control, changes)
+    }
+    fn check(&mut self, control: &mut PropagateControl) -> bool {
+        (**self).check(control)
+    }
+    fn decide(
+        &mut self,
+        thread_id: Id,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+        decision: &mut SolverLiteral,
+    ) -> bool {
+        (**self).decide(thread_id, assignment, fallback, decision)
+    }
+}
+/// Propagator wrapper setting a fixed check mode after initialization.
+struct CheckModePropagator<P: Propagator> {
+    propagator: P,
+    check_mode: PropagatorCheckMode,
+}
+impl<P: Propagator> Propagator for CheckModePropagator<P> {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let result = self.propagator.init(init);
+        init.set_check_mode(self.check_mode);
+        result
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        self.propagator.propagate(control, changes)
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        self.propagator.undo(control, changes)
+    }
+    fn check(&mut self, control: &mut PropagateControl) -> bool {
+        self.propagator.check(control)
+    }
+    fn decide(
+        &mut self,
+        thread_id: Id,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+        decision: &mut SolverLiteral,
+    ) -> bool {
+        self.propagator
+            .decide(thread_id, assignment, fallback, decision)
+    }
+}
 pub mod defaults {
     use crate::ast::Location;
     use crate::{
@@ -1716,6 +1842,8 @@ pub struct GenericControl<C: ControlCtx> {
     ctl: NonNull<clingo_control_t>,
     copied: bool,
     context: Box<C>,
+    extensions: Vec<Box<dyn Any>>,
+    externals: Option<ExternalValues>,
 }
 pub type Control = GenericControl<DefaultCtx>;
 impl<C: ControlCtx> Drop for GenericControl<C> {
@@ -1771,11 +1899,15 @@ impl<C: ControlCtx> GenericControl<C> {
     /// * `context` - implementing the trait [`ControlCtx`]
     pub fn register_control_context<T: ControlCtx>(mut self, context: T) -> GenericControl<T> {
         let context = Box::new(context);
+        let extensions = std::mem::take(&mut self.extensions);
+        let externals = std::mem::take(&mut self.externals);
         self.copied = true;
         GenericControl {
             ctl: self.ctl,
             copied: false,
             context,
+            extensions,
+            externals,
         }
     }
     /// Solve the currently grounded ([`Control::ground()`]) logic program
@@ -1964,6 +2096,7 @@ impl<C: ControlCtx> GenericControl<C> {
         literal: SolverLiteral,
         value: TruthValue,
     ) -> Result<(), ClingoError> {
+        let externals = self.external_values()?;
         if !unsafe {
             clingo_control_assign_external(
                 self.ctl.as_ptr(),
@@ -1975,6 +2108,15 @@ impl<C: ControlCtx> GenericControl<C> {
                 "Call to clingo_control_assign_external() failed",
             ));
         }
+        let (literal, value) = match (literal.0 < 0, value) {
+            (true, TruthValue::True) => (literal.negate(), TruthValue::False),
+            (true, TruthValue::False) => (literal.negate(), TruthValue::True),
+            (true, TruthValue::Free) => (literal.negate(), TruthValue::Free),
+            (false, value) => (literal, value),
+        };
+        if let Ok(mut externals) = externals.lock() {
+            externals.insert(literal, value);
+        }
         Ok(())
     }
     /// Release an external atom.
@@ -2001,27 +2143,63 @@ impl<C: ControlCtx> GenericControl<C> {
                 "Call to clingo_control_release_external() failed",
             ));
         }
+        if let Some(Ok(mut externals)) = self.externals.as_ref().map(|values| values.lock()) {
+            externals.remove(&SolverLiteral(literal.abs()));
+        }
         Ok(())
     }
-    /// Register a custom propagator with the control object.
+    /// Get the truth values assigned to external atoms.
+    ///
+    /// An observer is registered on first use that forgets the values of atoms declared
+    /// external again, released or defined by rules in later steps.
+    fn external_values(&mut self) -> Result<ExternalValues, ClingoError> {
+        if let Some(externals) = &self.externals {
+            return Ok(externals.clone());
+        }
+        let externals = ExternalValues::default();
+        let observer = ExternalObserver {
+            externals: externals.clone(),
+        };
+        self.register_observer(Box::new(observer), false)?;
+        self.externals = Some(externals.clone());
+        Ok(externals)
+    }
+    /// Register the propagator of the control context with the control object.
     ///
     /// # Errors
     ///
     /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
-    fn register_propagator(&mut self) -> Result<(), ClingoError> {
+    fn register_context_propagator(&mut self) -> Result<(), ClingoError> {
         let (propagator, sequential) = self.context.propagator();
+        let propagator = propagator as *mut C::P;
+        self.register_raw_propagator(propagator, sequential)?;
+        self.copied = true;
+        Ok(())
+    }
+    /// Register a propagator given as pointer with the control object.
+    ///
+    /// The caller has to ensure that the propagator outlives the control object.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    fn register_raw_propagator<P: Propagator>(
+        &mut self,
+        propagator: *mut P,
+        sequential: bool,
+    ) -> Result<(), ClingoError> {
         let clingo_propagator = clingo_propagator_t {
-            init: Some(unsafe_init::<C::P>),
-            propagate: Some(unsafe_propagate::<C::P>),
-            undo: Some(unsafe_undo::<C::P>),
-            check: Some(unsafe_check::<C::P>),
-            decide: Some(unsafe_decide::<C::P>),
+            init: Some(unsafe_init::<P>),
+            propagate: Some(unsafe_propagate::<P>),
+            undo: Some(unsafe_undo::<P>),
+            check: Some(unsafe_check::<P>),
+            decide: Some(unsafe_decide::<P>),
         };
         if !unsafe {
             clingo_control_register_propagator(
                 self.ctl.as_ptr(),
                 &clingo_propagator,
-                propagator as *mut C::P as *mut c_void,
+                propagator as *mut c_void,
                 sequential,
             )
         } {
@@ -2029,7 +2207,82 @@ impl<C: ControlCtx> GenericControl<C> {
                 "Call to clingo_control_register_propagator() failed",
             ));
         }
-        self.copied = true;
+        Ok(())
+    }
+    /// Register an additional propagator with the control object.
+    ///
+    /// Any number of propagators can be registered this way next to the one provided by the
+    /// [`ControlCtx`].
+    /// Propagators are called in the order of their registration.
+    /// The control object takes ownership of the propagator and keeps it alive until it is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `propagator` - implementing the trait [`Propagator`]
+    /// * `check_mode` - the check mode of the propagator (overrides a mode set in [`Propagator::init()`])
+    /// * `sequential` - whether the propagator is called sequentially (locked)
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn add_propagator<P: Propagator + Send + 'static>(
+        &mut self,
+        propagator: P,
+        check_mode: PropagatorCheckMode,
+        sequential: bool,
+    ) -> Result<(), ClingoError> {
+        let mut propagator = Box::new(CheckModePropagator {
+            propagator,
+            check_mode,
+        });
+        self.register_raw_propagator(propagator.as_mut() as *mut _, sequential)?;
+        self.extensions.push(propagator);
+        Ok(())
+    }
+    /// Register a decision heuristic with the control object.
+    ///
+    /// The heuristic is registered as a propagator that is called sequentially and never checks
+    /// assignments, see [`GenericControl::add_propagator()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `heuristic` - implementing the trait [`propagator::Heuristic`]
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn add_heuristic<H: propagator::Heuristic + Send + 'static>(
+        &mut self,
+        heuristic: H,
+    ) -> Result<(), ClingoError> {
+        self.add_propagator(
+            propagator::HeuristicPropagator::new(heuristic),
+            PropagatorCheckMode::None,
+            true,
+        )
+    }
+    /// Register a propagator with the control object at runtime.
+    ///
+    /// Unlike a propagator provided by a [`ControlCtx`], this does not change the type of the
+    /// control object, so that functions taking a plain [`Control`] can add propagators.
+    /// The propagator is called sequentially and keeps the check mode set in
+    /// [`Propagator::init()`].
+    /// Use [`GenericControl::add_propagator()`] to configure the check mode and lock-free calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `propagator` - implementing the trait [`Propagator`]
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn register_propagator(
+        &mut self,
+        propagator: Box<dyn Propagator + Send>,
+    ) -> Result<(), ClingoError> {
+        let mut propagator = Box::new(propagator);
+        self.register_raw_propagator(propagator.as_mut() as *mut _, true)?;
+        self.extensions.push(propagator);
         Ok(())
     }
 
@@ -2236,42 +2489,82 @@ impl<C: ControlCtx> GenericControl<C> {
     /// Register a program observer with the control object.
     ///
     /// **Returns** whether the call was successful
-    fn register_observer(&mut self) -> Result<(), ClingoError> {
+    fn register_context_observer(&mut self) -> Result<(), ClingoError> {
         let (observer, replace) = self.context.observer();
+        let observer = observer as *mut C::O;
+        self.register_raw_observer(observer, replace)?;
+        self.copied = true;
+        Ok(())
+    }
+    /// Register a ground program observer given as pointer with the control object.
+    ///
+    /// The caller has to ensure that the observer outlives the control object.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    fn register_raw_observer<O: GroundProgramObserver>(
+        &mut self,
+        observer: *mut O,
+        replace: bool,
+    ) -> Result<(), ClingoError> {
         let gpo = clingo_ground_program_observer_t {
-            init_program: Some(unsafe_init_program::<C::O>),
-            begin_step: Some(unsafe_begin_step::<C::O>),
-            end_step: Some(unsafe_end_step::<C::O>),
-            rule: Some(unsafe_rule::<C::O>),
-            weight_rule: Some(unsafe_weight_rule::<C::O>),
-            minimize: Some(unsafe_minimize::<C::O>),
-            project: Some(unsafe_project::<C::O>),
-            output_atom: Some(unsafe_output_atom::<C::O>),
-            output_term: Some(unsafe_output_term::<C::O>),
-            external: Some(unsafe_external::<C::O>),
-            assume: Some(unsafe_assume::<C::O>),
-            heuristic: Some(unsafe_heuristic::<C::O>),
-            acyc_edge: Some(unsafe_acyc_edge::<C::O>),
-            theory_term_number: Some(unsafe_theory_term_number::<C::O>),
-            theory_term_string: Some(unsafe_theory_term_string::<C::O>),
-            theory_term_compound: Some(unsafe_theory_term_compound::<C::O>),
-            theory_element: Some(unsafe_theory_element::<C::O>),
-            theory_atom: Some(unsafe_theory_atom::<C::O>),
-            theory_atom_with_guard: Some(unsafe_theory_atom_with_guard::<C::O>),
+            init_program: Some(unsafe_init_program::<O>),
+            begin_step: Some(unsafe_begin_step::<O>),
+            end_step: Some(unsafe_end_step::<O>),
+            rule: Some(unsafe_rule::<O>),
+            weight_rule: Some(unsafe_weight_rule::<O>),
+            minimize: Some(unsafe_minimize::<O>),
+            project: Some(unsafe_project::<O>),
+            output_atom: Some(unsafe_output_atom::<O>),
+            output_term: Some(unsafe_output_term::<O>),
+            external: Some(unsafe_external::<O>),
+            assume: Some(unsafe_assume::<O>),
+            heuristic: Some(unsafe_heuristic::<O>),
+            acyc_edge: Some(unsafe_acyc_edge::<O>),
+            theory_term_number: Some(unsafe_theory_term_number::<O>),
+            theory_term_string: Some(unsafe_theory_term_string::<O>),
+            theory_term_compound: Some(unsafe_theory_term_compound::<O>),
+            theory_element: Some(unsafe_theory_element::<O>),
+            theory_atom: Some(unsafe_theory_atom::<O>),
+            theory_atom_with_guard: Some(unsafe_theory_atom_with_guard::<O>),
         };
         if !unsafe {
             clingo_control_register_observer(
                 self.ctl.as_ptr(),
                 &gpo,
                 replace,
-                observer as *mut C::O as *mut c_void,
+                observer as *mut c_void,
             )
         } {
             return Err(ClingoError::new_internal(
                 "Call to clingo_control_register_observer() failed",
             ));
         }
-        self.copied = true;
+        Ok(())
+    }
+    /// Register a ground program observer with the control object at runtime.
+    ///
+    /// Unlike an observer provided by a [`ControlCtx`], this does not change the type of the
+    /// control object, so that functions taking a plain [`Control`] can add observers.
+    /// Multiple observers can be registered, they are called in the order of their registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - implementing the trait [`GroundProgramObserver`]
+    /// * `replace` - just pass the grounding to the observer but not the solver
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn register_observer(
+        &mut self,
+        observer: Box<dyn GroundProgramObserver + Send>,
+        replace: bool,
+    ) -> Result<(), ClingoError> {
+        let mut observer = Box::new(observer);
+        self.register_raw_observer(observer.as_mut() as *mut _, replace)?;
+        self.extensions.push(observer);
         Ok(())
     }
     /// Get an object to add ground directives to the program.
@@ -2301,6 +2594,20 @@ impl<C: ControlCtx> GenericControl<C> {
         }
     }
 
+    /// Get a backend to add directives over symbols, see [`SymbolicBackend`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] if the backend is not available
+    pub fn symbolic_backend(&mut self) -> Result<SymbolicBackend<'_>, ClingoError> {
+        Ok(SymbolicBackend::new(self.backend()?))
+    }
+
+    /// Get a handle to assign and release external atoms by their symbols, see [`Externals`].
+    pub fn externals(&mut self) -> Externals<'_, C> {
+        Externals { ctl: self }
+    }
+
     pub fn add_facts(&mut self, facts: &FactBase) -> Result<(), ClingoError> {
         for sym in facts.iter() {
             let loc = ast::Location::default();
@@ -2454,6 +2761,8 @@ pub fn control(arguments: std::vec::Vec<String>) -> Result<Control, ClingoError>
             ctl,
             copied: false,
             context: Box::new(DefaultCtx { non: defaults::Non }),
+            extensions: vec![],
+            externals: None,
         }),
         None => Err(ClingoError::FFIError {
             msg: "Tried creating NonNull from a null pointer.",
@@ -2516,9 +2825,11 @@ pub fn control_with_context<C: ControlCtx>(
                 ctl,
                 copied: false,
                 context: Box::new(context),
+                extensions: vec![],
+                externals: None,
             };
-            control.register_observer()?;
-            control.register_propagator()?;
+            control.register_context_observer()?;
+            control.register_context_propagator()?;
             Ok(control)
         }
         None => Err(ClingoError::FFIError {
@@ -2805,6 +3116,9 @@ impl Configuration {
 }
 
 /// Handle to the backend to add directives in aspif format.
+///
+/// **Note:** The backend of clingo 5.6 offers no directive for shown terms, atoms added with a
+/// symbol via [`Backend::add_atom()`] are shown like atoms of the program.
 #[derive(Debug)]
 pub struct Backend<'a> {
     theref: &'a mut clingo_backend_t,
@@ -3065,141 +3379,718 @@ impl<'a> Backend<'a> {
         Ok(())
     }
 
-    /// Get a fresh atom to be used in aspif directives.
+    /// Add a numeric theory term.
+    ///
     /// # Arguments
     ///
-    /// * `symbol` - optional symbol to associate the atom with
-    pub fn add_atom(&mut self, symbol: Option<Symbol>) -> Result<Atom, ClingoError> {
-        match symbol {
-            Some(Symbol(mut symbol)) => {
-                let mut atom = 0;
-                if unsafe { clingo_backend_add_atom(self.theref, &mut symbol, &mut atom) } {
-                    Ok(Atom(atom))
-                } else {
-                    Err(ClingoError::new_internal(
-                        "Call to clingo_backend_add_atom() failed",
-                    ))
-                }
-            }
-            None => {
-                let mut atom = 0;
-                let null = std::ptr::null_mut();
-                if unsafe { clingo_backend_add_atom(self.theref, null, &mut atom) } {
-                    Ok(Atom(atom))
-                } else {
-                    Err(ClingoError::new_internal(
-                        "Call to clingo_backend_add_atom() failed",
-                    ))
-                }
-            }
-        }
-    }
-}
-
-/// Handle for to the solver statistics.
-#[derive(Debug)]
-pub struct Statistics(clingo_statistics_t);
-impl Statistics {
-    /// Get the root key of the statistics.
-    pub fn root(&self) -> Result<u64, ClingoError> {
-        let mut root_key = 0;
-        if !unsafe { clingo_statistics_root(&self.0, &mut root_key) } {
-            return Err(ClingoError::new_internal(
-                "Call to clingo_statistics_root() failed",
-            ));
-        }
-        Ok(root_key)
-    }
-
-    /// Get the type of a key.
+    /// * `number` - the value of the term
     ///
-    /// # Arguments
+    /// **Returns** the id of the term
     ///
-    /// * `key` - the key
-    pub fn statistics_type(&self, key: u64) -> Result<StatisticsType, ClingoError> {
-        let mut stype = 0;
-        if !unsafe { clingo_statistics_type(&self.0, key, &mut stype) } {
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_term_number(&mut self, number: i32) -> Result<Id, ClingoError> {
+        let mut term_id = 0;
+        if !unsafe { clingo_backend_theory_term_number(self.theref, number, &mut term_id) } {
             return Err(ClingoError::new_internal(
-                "Call to clingo_statistics_type() failed",
+                "Call to clingo_backend_theory_term_number() failed",
             ));
         }
-        StatisticsType::try_from(stype as u32)
+        Ok(Id(term_id))
     }
 
-    /// Get the size of an array entry.
+    /// Add a theory term representing a string.
     ///
-    /// # Pre-condition
+    /// # Arguments
     ///
-    /// The [`Statistics::statistics_type()`] of the entry must be
-    /// [`StatisticsType::Array`].
+    /// * `string` - the value of the term
     ///
-    /// # Arguments
+    /// **Returns** the id of the term
     ///
-    /// * `key` - the key
-    pub fn array_size(&self, key: u64) -> Result<usize, ClingoError> {
-        let mut size = 0;
-        if !unsafe { clingo_statistics_array_size(&self.0, key, &mut size) } {
+    /// # Errors
+    ///
+    /// - [`ClingoError::NulError`] - if `string` contains a nul byte
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_term_string(&mut self, string: &str) -> Result<Id, ClingoError> {
+        let string = CString::new(string)?;
+        let mut term_id = 0;
+        if !unsafe { clingo_backend_theory_term_string(self.theref, string.as_ptr(), &mut term_id) }
+        {
             return Err(ClingoError::new_internal(
-                "Call to clingo_statistics_array_size() failed",
+                "Call to clingo_backend_theory_term_string() failed",
             ));
         }
-        Ok(size)
+        Ok(Id(term_id))
     }
 
-    /// Get the subkey at the given offset of an array entry.
+    /// Add a theory term representing a sequence of theory terms.
     ///
-    /// # Pre-condition
+    /// # Arguments
     ///
-    /// The [`Statistics::statistics_type()`] of the entry must be
-    /// [`StatisticsType::Array`].
+    /// * `sequence_type` - the type of the sequence
+    /// * `arguments` - the term ids of the terms in the sequence
     ///
-    /// # Arguments
+    /// **Returns** the id of the term
     ///
-    /// * `key` - the key
-    /// * `offset` - the offset in the array
-    pub fn array_at(&self, key: u64, offset: usize) -> Result<u64, ClingoError> {
-        let mut subkey = 0;
-        if !unsafe { clingo_statistics_array_at(&self.0, key, offset, &mut subkey) } {
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_term_sequence(
+        &mut self,
+        sequence_type: TheorySequenceType,
+        arguments: &[Id],
+    ) -> Result<Id, ClingoError> {
+        // clingo negates the given type to obtain the aspif tuple type, which is -1 for tuples,
+        // -2 for sets and -3 for lists
+        let sequence_type = match sequence_type {
+            TheorySequenceType::Tuple => 1,
+            TheorySequenceType::Set => 2,
+            TheorySequenceType::List => 3,
+        };
+        let mut term_id = 0;
+        if !unsafe {
+            clingo_backend_theory_term_sequence(
+                self.theref,
+                sequence_type,
+                arguments.as_ptr() as *const clingo_id_t,
+                arguments.len(),
+                &mut term_id,
+            )
+        } {
             return Err(ClingoError::new_internal(
-                "Call to clingo_statistics_array_at() failed",
+                "Call to clingo_backend_theory_term_sequence() failed",
             ));
         }
-        Ok(subkey)
+        Ok(Id(term_id))
     }
 
-    /// Create the subkey at the end of an array entry.
+    /// Add a theory term representing a function.
     ///
-    /// #Pre-condition
+    /// # Arguments
     ///
-    /// The [`Statistics::statistics_type()`] of the entry must
-    /// be [`StatisticsType::Array`]
+    /// * `name` - the name of the function
+    /// * `arguments` - the term ids of the arguments
     ///
-    /// # Arguments
+    /// **Returns** the id of the term
     ///
-    /// * `key` - the key
-    /// * `stype` -  the type of the new subkey
-    pub fn array_push(&mut self, key: u64, stype: StatisticsType) -> Result<u64, ClingoError> {
-        let mut subkey = 0;
+    /// # Errors
+    ///
+    /// - [`ClingoError::NulError`] - if `name` contains a nul byte
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_term_function(
+        &mut self,
+        name: &str,
+        arguments: &[Id],
+    ) -> Result<Id, ClingoError> {
+        let name = CString::new(name)?;
+        let mut term_id = 0;
         if !unsafe {
-            clingo_statistics_array_push(
-                &mut self.0,
-                key,
-                stype as clingo_statistics_type_t,
-                &mut subkey,
+            clingo_backend_theory_term_function(
+                self.theref,
+                name.as_ptr(),
+                arguments.as_ptr() as *const clingo_id_t,
+                arguments.len(),
+                &mut term_id,
             )
         } {
             return Err(ClingoError::new_internal(
-                "Call to clingo_statistics_array_push() failed",
+                "Call to clingo_backend_theory_term_function() failed",
             ));
         }
-        Ok(subkey)
+        Ok(Id(term_id))
     }
 
-    /// Get the number of subkeys of a map entry.
+    /// Convert the given symbol into a theory term.
     ///
-    /// # Pre-condition
+    /// # Arguments
     ///
-    /// The [`Statistics::statistics_type()`] of the entry must
+    /// * `symbol` - the symbol to convert
+    ///
+    /// **Returns** the id of the term
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_term_symbol(&mut self, Symbol(symbol): Symbol) -> Result<Id, ClingoError> {
+        let mut term_id = 0;
+        if !unsafe { clingo_backend_theory_term_symbol(self.theref, symbol, &mut term_id) } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_backend_theory_term_symbol() failed",
+            ));
+        }
+        Ok(Id(term_id))
+    }
+
+    /// Add a theory atom element.
+    ///
+    /// # Arguments
+    ///
+    /// * `tuple` - the term ids representing the tuple
+    /// * `condition` - the program literals representing the condition
+    ///
+    /// **Returns** the id of the element
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_element(
+        &mut self,
+        tuple: &[Id],
+        condition: &[SolverLiteral],
+    ) -> Result<Id, ClingoError> {
+        let mut element_id = 0;
+        if !unsafe {
+            clingo_backend_theory_element(
+                self.theref,
+                tuple.as_ptr() as *const clingo_id_t,
+                tuple.len(),
+                condition.as_ptr() as *const clingo_literal_t,
+                condition.len(),
+                &mut element_id,
+            )
+        } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_backend_theory_element() failed",
+            ));
+        }
+        Ok(Id(element_id))
+    }
+
+    /// Add a theory atom without a guard.
+    ///
+    /// # Arguments
+    ///
+    /// * `atom` - the program atom of the theory atom or `None` for theory directives
+    /// * `term` - the term id of the term associated with the theory atom
+    /// * `elements` - the element ids of the theory atom's elements
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_atom(
+        &mut self,
+        atom: Option<Atom>,
+        Id(term): Id,
+        elements: &[Id],
+    ) -> Result<(), ClingoError> {
+        let atom = atom.map_or(0, |Atom(atom)| atom);
+        if !unsafe {
+            clingo_backend_theory_atom(
+                self.theref,
+                atom,
+                term,
+                elements.as_ptr() as *const clingo_id_t,
+                elements.len(),
+            )
+        } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_backend_theory_atom() failed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add a theory atom with a guard.
+    ///
+    /// # Arguments
+    ///
+    /// * `atom` - the program atom of the theory atom or `None` for theory directives
+    /// * `term` - the term id of the term associated with the theory atom
+    /// * `elements` - the element ids of the theory atom's elements
+    /// * `operator_name` - the string representation of a theory operator
+    /// * `right_hand_side` - the term id of the right hand side term
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::NulError`] - if `operator_name` contains a nul byte
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn theory_atom_with_guard(
+        &mut self,
+        atom: Option<Atom>,
+        Id(term): Id,
+        elements: &[Id],
+        operator_name: &str,
+        Id(right_hand_side): Id,
+    ) -> Result<(), ClingoError> {
+        let atom = atom.map_or(0, |Atom(atom)| atom);
+        let operator_name = CString::new(operator_name)?;
+        if !unsafe {
+            clingo_backend_theory_atom_with_guard(
+                self.theref,
+                atom,
+                term,
+                elements.as_ptr() as *const clingo_id_t,
+                elements.len(),
+                operator_name.as_ptr(),
+                right_hand_side,
+            )
+        } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_backend_theory_atom_with_guard() failed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get a fresh atom to be used in aspif directives.
+    /// # Arguments
+    ///
+    /// * `symbol` - optional symbol to associate the atom with
+    pub fn add_atom(&mut self, symbol: Option<Symbol>) -> Result<Atom, ClingoError> {
+        match symbol {
+            Some(Symbol(mut symbol)) => {
+                let mut atom = 0;
+                if unsafe { clingo_backend_add_atom(self.theref, &mut symbol, &mut atom) } {
+                    Ok(Atom(atom))
+                } else {
+                    Err(ClingoError::new_internal(
+                        "Call to clingo_backend_add_atom() failed",
+                    ))
+                }
+            }
+            None => {
+                let mut atom = 0;
+                let null = std::ptr::null_mut();
+                if unsafe { clingo_backend_add_atom(self.theref, null, &mut atom) } {
+                    Ok(Atom(atom))
+                } else {
+                    Err(ClingoError::new_internal(
+                        "Call to clingo_backend_add_atom() failed",
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// A literal over a symbol, used to add rules with a [`SymbolicBackend`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SymbolicLiteral {
+    /// The atom of the symbol.
+    Positive(Symbol),
+    /// The default negation of the atom of the symbol.
+    Negative(Symbol),
+}
+
+/// Handle to the backend to add directives over symbols instead of aspif atoms.
+///
+/// An atom is allocated once per symbol and cached.
+/// Atoms are associated with their symbols, so they are shown in models unless the program
+/// restricts the output with `#show` directives.
+#[derive(Debug)]
+pub struct SymbolicBackend<'a> {
+    backend: Backend<'a>,
+    atoms: HashMap<Symbol, Atom>,
+}
+impl<'a> SymbolicBackend<'a> {
+    /// Create a symbolic backend adding directives to the given backend.
+    pub fn new(backend: Backend<'a>) -> SymbolicBackend<'a> {
+        SymbolicBackend {
+            backend,
+            atoms: HashMap::new(),
+        }
+    }
+
+    /// Get the underlying backend.
+    pub fn backend(&mut self) -> &mut Backend<'a> {
+        &mut self.backend
+    }
+
+    /// Get the atom associated with the given symbol, adding it if necessary.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn atom(&mut self, symbol: Symbol) -> Result<Atom, ClingoError> {
+        if let Some(atom) = self.atoms.get(&symbol) {
+            return Ok(*atom);
+        }
+        let atom = self.backend.add_atom(Some(symbol))?;
+        self.atoms.insert(symbol, atom);
+        Ok(atom)
+    }
+
+    /// Get the program literal of the given symbolic literal.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn literal(&mut self, literal: SymbolicLiteral) -> Result<SolverLiteral, ClingoError> {
+        match literal {
+            SymbolicLiteral::Positive(symbol) => Ok(self.atom(symbol)?.into()),
+            SymbolicLiteral::Negative(symbol) => {
+                Ok(SolverLiteral::from(self.atom(symbol)?).negate())
+            }
+        }
+    }
+
+    fn atoms(&mut self, symbols: &[Symbol]) -> Result<Vec<Atom>, ClingoError> {
+        symbols.iter().map(|symbol| self.atom(*symbol)).collect()
+    }
+
+    fn literals(
+        &mut self,
+        literals: &[SymbolicLiteral],
+    ) -> Result<Vec<SolverLiteral>, ClingoError> {
+        literals
+            .iter()
+            .map(|literal| self.literal(*literal))
+            .collect()
+    }
+
+    fn weighted_literals(
+        &mut self,
+        literals: &[(SymbolicLiteral, i32)],
+    ) -> Result<Vec<WeightedLiteral>, ClingoError> {
+        literals
+            .iter()
+            .map(|(literal, weight)| Ok(WeightedLiteral::new(self.literal(*literal)?, *weight)))
+            .collect()
+    }
+
+    /// Add the given symbol as a fact.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn fact(&mut self, symbol: Symbol) -> Result<(), ClingoError> {
+        let atom = self.atom(symbol)?;
+        self.backend.rule(false, &[atom], &[])
+    }
+
+    /// Add a disjunctive rule, an empty head yields an integrity constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `head` - the head symbols
+    /// * `body` - the body literals
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn rule(&mut self, head: &[Symbol], body: &[SymbolicLiteral]) -> Result<(), ClingoError> {
+        let head = self.atoms(head)?;
+        let body = self.literals(body)?;
+        self.backend.rule(false, &head, &body)
+    }
+
+    /// Add a choice rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `head` - the head symbols
+    /// * `body` - the body literals
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn choice_rule(
+        &mut self,
+        head: &[Symbol],
+        body: &[SymbolicLiteral],
+    ) -> Result<(), ClingoError> {
+        let head = self.atoms(head)?;
+        let body = self.literals(body)?;
+        self.backend.rule(true, &head, &body)
+    }
+
+    /// Add a weight rule, see [`Backend::weight_rule()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `choice` - determines if the head is a choice or a disjunction
+    /// * `head` - the head symbols
+    /// * `lower_bound` - the lower bound of the weight rule
+    /// * `body` - the weighted body literals
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn weight_rule(
+        &mut self,
+        choice: bool,
+        head: &[Symbol],
+        lower_bound: i32,
+        body: &[(SymbolicLiteral, i32)],
+    ) -> Result<(), ClingoError> {
+        let head = self.atoms(head)?;
+        let body = self.weighted_literals(body)?;
+        self.backend.weight_rule(choice, &head, lower_bound, &body)
+    }
+
+    /// Add a minimize constraint, see [`Backend::minimize()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - the priority of the constraint
+    /// * `literals` - the weighted literals whose sum to minimize
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn minimize(
+        &mut self,
+        priority: i32,
+        literals: &[(SymbolicLiteral, i32)],
+    ) -> Result<(), ClingoError> {
+        let literals = self.weighted_literals(literals)?;
+        self.backend.minimize(priority, &literals)
+    }
+
+    /// Add an external statement, see [`Backend::external()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn external(&mut self, symbol: Symbol, type_: ExternalType) -> Result<(), ClingoError> {
+        let atom = self.atom(symbol)?;
+        self.backend.external(atom, type_)
+    }
+
+    /// Add an assumption directive, see [`Backend::assume()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn assume(&mut self, literals: &[SymbolicLiteral]) -> Result<(), ClingoError> {
+        let literals = self.literals(literals)?;
+        self.backend.assume(&literals)
+    }
+}
+
+/// Truth values assigned to external atoms, see [`Externals`].
+type ExternalValues = Arc<Mutex<HashMap<SolverLiteral, TruthValue>>>;
+
+/// Observer forgetting the assigned truth values of atoms that are grounded again.
+struct ExternalObserver {
+    externals: ExternalValues,
+}
+impl ExternalObserver {
+    fn forget(&mut self, atoms: &[Atom]) -> bool {
+        if let Ok(mut externals) = self.externals.lock() {
+            for Atom(atom) in atoms {
+                externals.remove(&SolverLiteral(*atom as i32));
+            }
+        }
+        true
+    }
+}
+impl GroundProgramObserver for ExternalObserver {
+    fn rule(&mut self, _choice: bool, head: &[Atom], _body: &[SolverLiteral]) -> bool {
+        self.forget(head)
+    }
+    fn weight_rule(
+        &mut self,
+        _choice: bool,
+        head: &[Atom],
+        _lower_bound: i32,
+        _body: &[WeightedLiteral],
+    ) -> bool {
+        self.forget(head)
+    }
+    fn external(&mut self, atom: Atom, _type_: ExternalType) -> bool {
+        self.forget(&[atom])
+    }
+}
+
+/// Handle to assign and release external atoms by their symbols.
+///
+/// The truth values assigned with [`GenericControl::assign_external()`], directly or via the
+/// handle, are tracked by the control object.
+/// A value is forgotten when the external is released, or when a later step declares the atom
+/// external again or defines it by a rule, because these reset the value of the atom.
+/// Externals that have not been assigned have the truth value given in their `#external`
+/// directive, which is false by default.
+#[derive(Debug)]
+pub struct Externals<'a, C: ControlCtx> {
+    ctl: &'a mut GenericControl<C>,
+}
+impl<'a, C: ControlCtx> Externals<'a, C> {
+    /// Get the literal of the external atom of the given symbol.
+    fn literal(&self, symbol: Symbol) -> Result<SolverLiteral, ClingoError> {
+        let signature = Signature::new(
+            symbol.name()?,
+            symbol.arguments()?.len() as u32,
+            symbol.is_positive()?,
+        )?;
+        for atom in self.ctl.symbolic_atoms()?.iter_with_signature(signature)? {
+            if atom.symbol()? == symbol {
+                if atom.is_external()? {
+                    return atom.literal();
+                }
+                break;
+            }
+        }
+        Err(ClingoError::new_external("Symbol is not an external atom"))
+    }
+
+    /// Assign a truth value to an external atom.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - the symbol of the external atom
+    /// * `value` - the truth value
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the symbol is not an external atom
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn assign(&mut self, symbol: &dyn ToSymbol, value: TruthValue) -> Result<(), ClingoError> {
+        let literal = self.literal(symbol.symbol()?)?;
+        self.ctl.assign_external(literal, value)
+    }
+
+    /// Release an external atom, see [`GenericControl::release_external()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the symbol is not an external atom
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn release(&mut self, symbol: &dyn ToSymbol) -> Result<(), ClingoError> {
+        let literal = self.literal(symbol.symbol()?)?;
+        self.ctl.release_external(literal)
+    }
+
+    /// Get the truth value assigned to an external atom.
+    ///
+    /// **Returns** `None` if the truth value is unknown because the external has not been
+    /// assigned, so that the value of its `#external` directive applies
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the symbol is not an external atom
+    pub fn value(&self, symbol: &dyn ToSymbol) -> Result<Option<TruthValue>, ClingoError> {
+        let literal = self.literal(symbol.symbol()?)?;
+        Ok(match &self.ctl.externals {
+            Some(externals) => externals
+                .lock()
+                .ok()
+                .and_then(|externals| externals.get(&literal).copied()),
+            None => None,
+        })
+    }
+
+    /// Get the symbols of the external atoms with the given signature.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] if the symbolic atoms could not be accessed
+    pub fn list(&self, signature: Signature) -> Result<Vec<Symbol>, ClingoError> {
+        let mut symbols = vec![];
+        for atom in self.ctl.symbolic_atoms()?.iter_with_signature(signature)? {
+            if atom.is_external()? {
+                symbols.push(atom.symbol()?);
+            }
+        }
+        Ok(symbols)
+    }
+}
+
+/// Handle for to the solver statistics.
+#[derive(Debug)]
+pub struct Statistics(clingo_statistics_t);
+impl Statistics {
+    /// Get the root key of the statistics.
+    pub fn root(&self) -> Result<u64, ClingoError> {
+        let mut root_key = 0;
+        if !unsafe { clingo_statistics_root(&self.0, &mut root_key) } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_statistics_root() failed",
+            ));
+        }
+        Ok(root_key)
+    }
+
+    /// Get the type of a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    pub fn statistics_type(&self, key: u64) -> Result<StatisticsType, ClingoError> {
+        let mut stype = 0;
+        if !unsafe { clingo_statistics_type(&self.0, key, &mut stype) } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_statistics_type() failed",
+            ));
+        }
+        StatisticsType::try_from(stype as u32)
+    }
+
+    /// Get the size of an array entry.
+    ///
+    /// # Pre-condition
+    ///
+    /// The [`Statistics::statistics_type()`] of the entry must be
+    /// [`StatisticsType::Array`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    pub fn array_size(&self, key: u64) -> Result<usize, ClingoError> {
+        let mut size = 0;
+        if !unsafe { clingo_statistics_array_size(&self.0, key, &mut size) } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_statistics_array_size() failed",
+            ));
+        }
+        Ok(size)
+    }
+
+    /// Get the subkey at the given offset of an array entry.
+    ///
+    /// # Pre-condition
+    ///
+    /// The [`Statistics::statistics_type()`] of the entry must be
+    /// [`StatisticsType::Array`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    /// * `offset` - the offset in the array
+    pub fn array_at(&self, key: u64, offset: usize) -> Result<u64, ClingoError> {
+        let mut subkey = 0;
+        if !unsafe { clingo_statistics_array_at(&self.0, key, offset, &mut subkey) } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_statistics_array_at() failed",
+            ));
+        }
+        Ok(subkey)
+    }
+
+    /// Create the subkey at the end of an array entry.
+    ///
+    /// #Pre-condition
+    ///
+    /// The [`Statistics::statistics_type()`] of the entry must
+    /// be [`StatisticsType::Array`]
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the key
+    /// * `stype` -  the type of the new subkey
+    pub fn array_push(&mut self, key: u64, stype: StatisticsType) -> Result<u64, ClingoError> {
+        let mut subkey = 0;
+        if !unsafe {
+            clingo_statistics_array_push(
+                &mut self.0,
+                key,
+                stype as clingo_statistics_type_t,
+                &mut subkey,
+            )
+        } {
+            return Err(ClingoError::new_internal(
+                "Call to clingo_statistics_array_push() failed",
+            ));
+        }
+        Ok(subkey)
+    }
+
+    /// Get the number of subkeys of a map entry.
+    ///
+    /// # Pre-condition
+    ///
+    /// The [`Statistics::statistics_type()`] of the entry must
     /// be [`StatisticsType::Map`].
     ///
     /// # Arguments
@@ -3685,7 +4576,7 @@ impl TheoryAtoms {
                 "Call to clingo_theory_atoms_term_arguments() failed",
             ));
         }
-        let arguments_ref = unsafe { std::slice::from_raw_parts(c_ptr as *const Id, size) };
+        let arguments_ref = unsafe { raw_slice(c_ptr as *const Id, size) };
         Ok(arguments_ref)
     }
 
@@ -3736,7 +4627,7 @@ impl TheoryAtoms {
                 "Call to clingo_theory_atoms_element_tuple() failed",
             ));
         }
-        let tuple_ref = unsafe { std::slice::from_raw_parts(tuple_ptr as *const Id, size) };
+        let tuple_ref = unsafe { raw_slice(tuple_ptr as *const Id, size) };
         Ok(tuple_ref)
     }
 
@@ -3755,8 +4646,7 @@ impl TheoryAtoms {
                 "Call to clingo_theory_atoms_element_condition() failed",
             ));
         }
-        let condition_ref =
-            unsafe { std::slice::from_raw_parts(condition_ptr as *const SolverLiteral, size) };
+        let condition_ref = unsafe { raw_slice(condition_ptr as *const SolverLiteral, size) };
         Ok(condition_ref)
     }
 
@@ -3771,18 +4661,13 @@ impl TheoryAtoms {
     ///
     /// * `element` - id of the element
     pub fn element_condition_id(&self, Id(element): Id) -> Result<SolverLiteral, ClingoError> {
-        let condition_ptr = std::ptr::null_mut();
-        if !unsafe { clingo_theory_atoms_element_condition_id(&self.0, element, condition_ptr) } {
+        let mut condition = 0;
+        if !unsafe { clingo_theory_atoms_element_condition_id(&self.0, element, &mut condition) } {
             return Err(ClingoError::new_internal(
                 "Call to clingo_theory_atoms_element_condition_id() failed",
             ));
         }
-        if condition_ptr.is_null() {
-            return Err(ClingoError::FFIError {
-                msg: "clingo_theory_atoms_element_condition_id() returned a null pointer.",
-            });
-        }
-        Ok(SolverLiteral(unsafe { *condition_ptr }))
+        Ok(SolverLiteral(condition))
     }
 
     // NODO: fn clingo_theory_atoms_element_to_string_size()
@@ -3847,7 +4732,7 @@ impl TheoryAtoms {
                 "Call to clingo_theory_atoms_atom_elements() failed",
             ));
         }
-        let elements = unsafe { std::slice::from_raw_parts(elements_ptr as *const Id, size) };
+        let elements = unsafe { raw_slice(elements_ptr as *const Id, size) };
         Ok(elements)
     }
 
@@ -3911,6 +4796,100 @@ impl TheoryAtoms {
         Ok(SolverLiteral(literal))
     }
 
+    /// Decode the given theory term into an owned term.
+    ///
+    /// # Arguments
+    ///
+    /// * `term` - id of the term
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::Utf8Error`]
+    /// - [`ClingoError::InternalError`]
+    pub fn term(&self, term: Id) -> Result<TheoryTermView, ClingoError> {
+        let arguments = |atoms: &TheoryAtoms| -> Result<Vec<TheoryTermView>, ClingoError> {
+            atoms
+                .term_arguments(term)?
+                .iter()
+                .map(|argument| atoms.term(*argument))
+                .collect()
+        };
+        Ok(match self.term_type(term)? {
+            TheoryTermType::Number => TheoryTermView::Number(self.term_number(term)?),
+            TheoryTermType::Symbol => TheoryTermView::Symbol(self.term_name(term)?.to_owned()),
+            TheoryTermType::Function => {
+                TheoryTermView::Function(self.term_name(term)?.to_owned(), arguments(self)?)
+            }
+            TheoryTermType::Tuple => TheoryTermView::Tuple(arguments(self)?),
+            TheoryTermType::List => TheoryTermView::List(arguments(self)?),
+            TheoryTermType::Set => TheoryTermView::Set(arguments(self)?),
+        })
+    }
+
+    /// Decode the given theory element into an owned element.
+    ///
+    /// # Arguments
+    ///
+    /// * `element` - id of the element
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::Utf8Error`]
+    /// - [`ClingoError::InternalError`]
+    pub fn element(&self, element: Id) -> Result<TheoryElementView, ClingoError> {
+        let tuple = self
+            .element_tuple(element)?
+            .iter()
+            .map(|term| self.term(*term))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TheoryElementView {
+            tuple,
+            condition: self.element_condition(element)?.to_vec(),
+            condition_id: self.element_condition_id(element)?,
+        })
+    }
+
+    /// Decode the given theory atom into an owned atom.
+    ///
+    /// # Arguments
+    ///
+    /// * `atom` - id of the atom
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::Utf8Error`]
+    /// - [`ClingoError::InternalError`]
+    pub fn atom(&self, atom: Id) -> Result<TheoryAtomView, ClingoError> {
+        let elements = self
+            .atom_elements(atom)?
+            .iter()
+            .map(|element| self.element(*element))
+            .collect::<Result<Vec<_>, _>>()?;
+        let guard = if self.atom_has_guard(atom)? {
+            let (operator, term) = self.atom_guard(atom)?;
+            Some((operator.to_owned(), self.term(term)?))
+        } else {
+            None
+        };
+        Ok(TheoryAtomView {
+            id: atom,
+            literal: self.atom_literal(atom)?,
+            term: self.term(self.atom_term(atom)?)?,
+            elements,
+            guard,
+        })
+    }
+
+    /// Decode all theory atoms into owned atoms.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::Utf8Error`]
+    /// - [`ClingoError::InternalError`]
+    pub fn views(&self) -> Result<Vec<TheoryAtomView>, ClingoError> {
+        self.iter().map(|atom| self.atom(atom)).collect()
+    }
+
     // NODO: fn clingo_theory_atoms_atom_to_string_size()
 
     /// Get the string representation of the given theory atom.
@@ -3944,6 +4923,140 @@ impl TheoryAtoms {
     }
 }
 
+/// Owned representation of a theory term.
+///
+/// See [`TheoryAtoms::term()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TheoryTermView {
+    /// A number term, e.g., `42`
+    Number(i32),
+    /// A symbol term, e.g., `c`
+    Symbol(String),
+    /// A function term, e.g., `f(1,2,3)`, operators are functions too, e.g., `x-y`
+    Function(String, Vec<TheoryTermView>),
+    /// A tuple term, e.g., `(1,2,3)`
+    Tuple(Vec<TheoryTermView>),
+    /// A list term, e.g., `[1,2,3]`
+    List(Vec<TheoryTermView>),
+    /// A set term, e.g., `{1,2,3}`
+    Set(Vec<TheoryTermView>),
+}
+impl TheoryTermView {
+    /// Get the name of a symbol or function term.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            TheoryTermView::Symbol(name) | TheoryTermView::Function(name, _) => Some(name),
+            _ => None,
+        }
+    }
+    /// Get the arguments of a function term or the elements of a tuple, list or set term.
+    pub fn arguments(&self) -> &[TheoryTermView] {
+        match self {
+            TheoryTermView::Function(_, arguments)
+            | TheoryTermView::Tuple(arguments)
+            | TheoryTermView::List(arguments)
+            | TheoryTermView::Set(arguments) => arguments,
+            _ => &[],
+        }
+    }
+    /// Convert the term into a symbol.
+    ///
+    /// Numbers, symbols, functions with identifier names and tuples are converted directly;
+    /// the operators `-` (unary) and `+`, `-`, `*`, `/` (binary) are evaluated on numbers.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the term cannot be represented as a symbol
+    /// - [`ClingoError::NulError`] if a name contains a nul byte
+    pub fn to_symbol(&self) -> Result<Symbol, ClingoError> {
+        match self {
+            TheoryTermView::Number(number) => Ok(Symbol::create_number(*number)),
+            TheoryTermView::Symbol(name) => Symbol::create_id(name, true),
+            TheoryTermView::Tuple(arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(TheoryTermView::to_symbol)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Symbol::create_function("", &arguments, true)
+            }
+            TheoryTermView::Function(name, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(TheoryTermView::to_symbol)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let number = |symbol: &Symbol| symbol.number().ok();
+                match (name.as_str(), arguments.as_slice()) {
+                    ("-", [x]) => number(x)
+                        .and_then(i32::checked_neg)
+                        .map(Symbol::create_number)
+                        .ok_or(ClingoError::new_external(
+                            "Could not convert theory term to symbol",
+                        )),
+                    ("+" | "-" | "*" | "/", [x, y]) => {
+                        let value = match (number(x), number(y), name.as_str()) {
+                            (Some(x), Some(y), "+") => x.checked_add(y),
+                            (Some(x), Some(y), "-") => x.checked_sub(y),
+                            (Some(x), Some(y), "*") => x.checked_mul(y),
+                            (Some(x), Some(y), _) => x.checked_div(y),
+                            _ => None,
+                        };
+                        value
+                            .map(Symbol::create_number)
+                            .ok_or(ClingoError::new_external(
+                                "Could not convert theory term to symbol",
+                            ))
+                    }
+                    _ if name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_') => {
+                        Symbol::create_function(name, &arguments, true)
+                    }
+                    _ => Err(ClingoError::new_external(
+                        "Could not convert theory term to symbol",
+                    )),
+                }
+            }
+            TheoryTermView::List(_) | TheoryTermView::Set(_) => Err(ClingoError::new_external(
+                "Could not convert theory term to symbol",
+            )),
+        }
+    }
+}
+
+/// Owned representation of a theory atom element.
+///
+/// See [`TheoryAtoms::element()`].
+#[derive(Debug, Clone)]
+pub struct TheoryElementView {
+    /// The tuple of terms of the element
+    pub tuple: Vec<TheoryTermView>,
+    /// The program literals of the condition
+    pub condition: Vec<SolverLiteral>,
+    /// The id of the condition, see [`TheoryAtoms::element_condition_id()`]
+    pub condition_id: SolverLiteral,
+}
+
+/// Owned representation of a theory atom.
+///
+/// See [`TheoryAtoms::atom()`].
+#[derive(Debug, Clone)]
+pub struct TheoryAtomView {
+    /// The id of the atom
+    pub id: Id,
+    /// The program literal of the atom
+    pub literal: SolverLiteral,
+    /// The term of the atom, e.g., `sum` for `&sum{...}`
+    pub term: TheoryTermView,
+    /// The elements of the atom
+    pub elements: Vec<TheoryElementView>,
+    /// The guard consisting of an operator and a term
+    pub guard: Option<(String, TheoryTermView)>,
+}
+impl TheoryAtomView {
+    /// Get the name of the atom, e.g., `sum` for `&sum{...}`.
+    pub fn name(&self) -> &str {
+        self.term.name().unwrap_or_default()
+    }
+}
+
 /// Iterator over theory atoms.
 pub struct TheoryAtomsIterator<'a> {
     count: usize,
@@ -4148,6 +5261,100 @@ impl Model {
         }
         unsafe { Ok(&mut *(control_ptr as *mut SolveControl)) }
     }
+
+    /// Decode the shown symbols of the model with the given signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - the signature of the symbols to decode
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    /// - [`ClingoError::DecodeError`] if a symbol cannot be converted to `T`
+    ///
+    /// **See:** [`ModelView`]
+    pub fn decode<T: FromSymbol>(&self, signature: &Signature) -> Result<Vec<T>, ClingoError>
+    where
+        ClingoError: From<T::Error>,
+    {
+        let view = ModelView::new(&self.symbols(ShowType::SHOWN)?);
+        view.decode(signature)
+    }
+
+    /// Get a view on the symbols of the selected types grouped by their signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `show` - which symbols to select
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn view(&self, show: ShowType) -> Result<ModelView, ClingoError> {
+        Ok(ModelView::new(&self.symbols(show)?))
+    }
+}
+
+/// The symbols of a model grouped by their signature.
+///
+/// Symbols that are not functions, like numbers or strings, do not have a signature and are
+/// not part of the view.
+///
+/// ```ignore
+/// let view = model.view(ShowType::SHOWN)?;
+/// let edges: Vec<Edge> = view.decode(&Signature::new("edge", 2, true)?)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ModelView {
+    symbols: HashMap<Signature, Vec<Symbol>>,
+}
+impl ModelView {
+    /// Group the given symbols by their signature.
+    pub fn new(symbols: &[Symbol]) -> ModelView {
+        let mut view = ModelView::default();
+        for symbol in symbols {
+            if let Ok(signature) = symbol.signature() {
+                view.symbols.entry(signature).or_default().push(*symbol);
+            }
+        }
+        view
+    }
+
+    /// Get the signatures of the symbols in the view in sorted order.
+    pub fn signatures(&self) -> Vec<Signature> {
+        let mut signatures: Vec<Signature> = self.symbols.keys().copied().collect();
+        signatures.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        signatures
+    }
+
+    /// Get the symbols with the given signature in the order of the model.
+    pub fn symbols(&self, signature: &Signature) -> &[Symbol] {
+        self.symbols
+            .get(signature)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Decode the symbols with the given signature.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::DecodeError`] if a symbol cannot be converted to `T`
+    pub fn decode<T: FromSymbol>(&self, signature: &Signature) -> Result<Vec<T>, ClingoError>
+    where
+        ClingoError: From<T::Error>,
+    {
+        self.symbols(signature)
+            .iter()
+            .map(|symbol| {
+                T::from_symbol(*symbol).map_err(|e| ClingoError::DecodeError {
+                    symbol: symbol.to_string(),
+                    source: Box::new(e.into()),
+                })
+            })
+            .collect()
+    }
 }
 
 /// Object to add clauses during search.
@@ -4631,6 +5838,50 @@ impl PropagateInit {
         Ok(SolverLiteral(solver_literal))
     }
 
+    /// Map the atoms with the given signatures to their solver literals.
+    ///
+    /// # Arguments
+    ///
+    /// * `signatures` - the signatures of the atoms to map
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn map_symbols(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<propagator::SymbolMap, ClingoError> {
+        let mut map = propagator::SymbolMap::new();
+        let atoms = self.symbolic_atoms()?;
+        for signature in signatures {
+            for atom in atoms.iter_with_signature(*signature)? {
+                let literal = self.solver_literal(atom.literal()?)?;
+                map.insert(atom.symbol()?, literal);
+            }
+        }
+        Ok(map)
+    }
+    /// Map the atoms with the given signatures to their solver literals and watch the literals.
+    ///
+    /// See [`PropagateInit::map_symbols()`] and [`PropagateInit::add_watch()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `signatures` - the signatures of the atoms to map
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
+    pub fn watch_symbols(
+        &mut self,
+        signatures: &[Signature],
+    ) -> Result<propagator::SymbolMap, ClingoError> {
+        let map = self.map_symbols(signatures)?;
+        for literal in map.literals() {
+            self.add_watch(literal)?;
+        }
+        Ok(map)
+    }
     /// Add a watch for the solver literal in the given phase.
     ///
     /// # Arguments
@@ -4763,19 +6014,14 @@ impl PropagateInit {
     /// # Errors
     ///
     /// - [`ClingoError::InternalError`] with [`ErrorCode::BadAlloc`]
-    pub fn add_literal(&mut self, freeze: bool) -> Result<&mut SolverLiteral, ClingoError> {
-        let literal_ptr = std::ptr::null_mut() as *mut clingo_literal_t;
-        if !unsafe { clingo_propagate_init_add_literal(&mut self.0, freeze, literal_ptr) } {
+    pub fn add_literal(&mut self, freeze: bool) -> Result<SolverLiteral, ClingoError> {
+        let mut literal = 0;
+        if !unsafe { clingo_propagate_init_add_literal(&mut self.0, freeze, &mut literal) } {
             return Err(ClingoError::new_internal(
                 "Call to clingo_propagate_init_add_literal() failed",
             ));
         }
-        match unsafe { (literal_ptr as *mut SolverLiteral).as_mut() } {
-            Some(x) => Ok(x),
-            None => Err(ClingoError::FFIError {
-                msg: "Tried casting a null pointer to &mutSolverLiteral.",
-            }),
-        }
+        Ok(SolverLiteral(literal))
     }
 
     /// Add the given clause to the solver.
@@ -5174,6 +6420,12 @@ pub struct MModel {
     pub model_type: ModelType,
     pub number: u64,
 }
+impl MModel {
+    /// Get a view on the symbols of the model grouped by their signature.
+    pub fn view(&self) -> ModelView {
+        ModelView::new(&self.symbols)
+    }
+}
 // #[doc = "! Callback to customize clingo main function."]
 // #[doc = "!"]
 // #[doc = "! @param[in] control corresponding control object"]
@@ -5765,6 +7017,104 @@ pub trait GroundProgramObserver {
         true
     }
 }
+impl<O: GroundProgramObserver + ?Sized> GroundProgramObserver for Box<O> {
+    fn init_program(&mut self, incremental: bool) -> bool {
+        (**self).init_program(incremental)
+    }
+    fn begin_step(&mut self) -> bool {
+        (**self).begin_step()
+    }
+    fn end_step(&mut self) -> bool {
+        (**self).end_step()
+    }
+    fn rule(&mut self, choice: bool, head: &[Atom], body: &[SolverLiteral]) -> bool {
+        (**self).rule(choice, head, body)
+    }
+    fn weight_rule(
+        &mut self,
+        choice: bool,
+        head: &[Atom],
+        lower_bound: i32,
+        body: &[WeightedLiteral],
+    ) -> bool {
+        (**self).weight_rule(choice, head, lower_bound, body)
+    }
+    fn minimize(&mut self, priority: i32, literals: &[WeightedLiteral]) -> bool {
+        (**self).minimize(priority, literals)
+    }
+    fn project(&mut self, atoms: &[Atom]) -> bool {
+        (**self).project(atoms)
+    }
+    fn output_atom(&mut self, symbol: Symbol, atom: Atom) -> bool {
+        (**self).output_atom(symbol, atom)
+    }
+    fn output_term(&mut self, symbol: Symbol, condition: &[SolverLiteral]) -> bool {
+        (**self).output_term(symbol, condition)
+    }
+    fn output_csp(&mut self, symbol: Symbol, value: i32, condition: &[SolverLiteral]) -> bool {
+        (**self).output_csp(symbol, value, condition)
+    }
+    fn external(&mut self, atom: Atom, type_: ExternalType) -> bool {
+        (**self).external(atom, type_)
+    }
+    fn assume(&mut self, literals: &[SolverLiteral]) -> bool {
+        (**self).assume(literals)
+    }
+    fn heuristic(
+        &mut self,
+        atom: Atom,
+        type_: HeuristicType,
+        bias: i32,
+        priority: u32,
+        condition: &[SolverLiteral],
+    ) -> bool {
+        (**self).heuristic(atom, type_, bias, priority, condition)
+    }
+    fn acyc_edge(&mut self, node_u: i32, node_v: i32, condition: &[SolverLiteral]) -> bool {
+        (**self).acyc_edge(node_u, node_v, condition)
+    }
+    fn theory_term_number(&mut self, term_id: Id, number: i32) -> bool {
+        (**self).theory_term_number(term_id, number)
+    }
+    fn theory_term_string(&mut self, term_id: Id, name: &str) -> bool {
+        (**self).theory_term_string(term_id, name)
+    }
+    fn theory_term_compound(
+        &mut self,
+        term_id: Id,
+        name_id_or_type: i32,
+        arguments: &[Id],
+    ) -> bool {
+        (**self).theory_term_compound(term_id, name_id_or_type, arguments)
+    }
+    fn theory_element(
+        &mut self,
+        element_id: Id,
+        terms: &[Id],
+        condition: &[SolverLiteral],
+    ) -> bool {
+        (**self).theory_element(element_id, terms, condition)
+    }
+    fn theory_atom(&mut self, atom_id_or_zero: Id, term_id: Id, elements: &[Id]) -> bool {
+        (**self).theory_atom(atom_id_or_zero, term_id, elements)
+    }
+    fn theory_atom_with_guard(
+        &mut self,
+        atom_id_or_zero: Id,
+        term_id: Id,
+        elements: &[Id],
+        operator_id: Id,
+        right_hand_side_id: Id,
+    ) -> bool {
+        (**self).theory_atom_with_guard(
+            atom_id_or_zero,
+            term_id,
+            elements,
+            operator_id,
+            right_hand_side_id,
+        )
+    }
+}
 unsafe extern "C" fn unsafe_init_program<T: GroundProgramObserver>(
     incremental: bool,
     gpo: *mut c_void,
@@ -5814,8 +7164,8 @@ unsafe extern "C" fn unsafe_rule<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_rule() got a null pointer.");
         return false;
     }
-    let head = std::slice::from_raw_parts(head as *const Atom, head_size);
-    let body = std::slice::from_raw_parts(body as *const SolverLiteral, body_size);
+    let head = raw_slice(head as *const Atom, head_size);
+    let body = raw_slice(body as *const SolverLiteral, body_size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.rule(choice, head, body)
@@ -5831,7 +7181,7 @@ unsafe extern "C" fn unsafe_minimize<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_minimize() got a null pointer.");
         return false;
     }
-    let literals = std::slice::from_raw_parts(literals as *const WeightedLiteral, size);
+    let literals = raw_slice(literals as *const WeightedLiteral, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.minimize(priority, literals)
@@ -5846,7 +7196,7 @@ unsafe extern "C" fn unsafe_project<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_project() got a null pointer.");
         return false;
     }
-    let atoms = std::slice::from_raw_parts(atoms as *const Atom, size);
+    let atoms = raw_slice(atoms as *const Atom, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.project(atoms)
@@ -5868,8 +7218,8 @@ unsafe extern "C" fn unsafe_weight_rule<T: GroundProgramObserver>(
         );
         return false;
     }
-    let head = std::slice::from_raw_parts(head as *const Atom, head_size);
-    let body = std::slice::from_raw_parts(body as *const WeightedLiteral, body_size);
+    let head = raw_slice(head as *const Atom, head_size);
+    let body = raw_slice(body as *const WeightedLiteral, body_size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.weight_rule(choice, head, lower_bound, body)
@@ -5903,7 +7253,7 @@ unsafe extern "C" fn unsafe_output_term<T: GroundProgramObserver>(
         );
         return false;
     }
-    let condition = std::slice::from_raw_parts(condition as *const SolverLiteral, size);
+    let condition = raw_slice(condition as *const SolverLiteral, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.output_term(Symbol(symbol), condition)
@@ -5939,7 +7289,7 @@ unsafe extern "C" fn unsafe_assume<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_assume() got a null pointer.");
         return false;
     }
-    let literals = std::slice::from_raw_parts(literals as *const SolverLiteral, size);
+    let literals = raw_slice(literals as *const SolverLiteral, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.assume(literals)
@@ -5958,7 +7308,7 @@ unsafe extern "C" fn unsafe_heuristic<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_heuristic() got a null pointer.");
         return false;
     }
-    let condition = std::slice::from_raw_parts(condition as *const SolverLiteral, size);
+    let condition = raw_slice(condition as *const SolverLiteral, size);
     let gpo = &mut *(gpo as *mut T);
 
     match HeuristicType::try_from(htype as u32) {
@@ -5982,7 +7332,7 @@ unsafe extern "C" fn unsafe_acyc_edge<T: GroundProgramObserver>(
         set_internal_error(ErrorType::Runtime, "unsafe_heuristic() got a null pointer.");
         return false;
     }
-    let condition = std::slice::from_raw_parts(condition as *const SolverLiteral, size);
+    let condition = raw_slice(condition as *const SolverLiteral, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.acyc_edge(node_u, node_v, condition)
@@ -6045,7 +7395,7 @@ unsafe extern "C" fn unsafe_theory_term_compound<T: GroundProgramObserver>(
         );
         return false;
     }
-    let arguments = std::slice::from_raw_parts(arguments as *const Id, size);
+    let arguments = raw_slice(arguments as *const Id, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.theory_term_compound(Id(term_id), name_id_or_type, arguments)
@@ -6069,8 +7419,8 @@ unsafe extern "C" fn unsafe_theory_element<T: GroundProgramObserver>(
         );
         return false;
     }
-    let terms = std::slice::from_raw_parts(terms as *const Id, terms_size);
-    let condition = std::slice::from_raw_parts(condition as *const SolverLiteral, condition_size);
+    let terms = raw_slice(terms as *const Id, terms_size);
+    let condition = raw_slice(condition as *const SolverLiteral, condition_size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.theory_element(Id(element_id), terms, condition)
@@ -6090,7 +7440,7 @@ unsafe extern "C" fn unsafe_theory_atom<T: GroundProgramObserver>(
         );
         return false;
     }
-    let elements = std::slice::from_raw_parts(elements as *const Id, size);
+    let elements = raw_slice(elements as *const Id, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.theory_atom(Id(atom_id_or_zero), Id(term_id), elements)
@@ -6112,7 +7462,7 @@ unsafe extern "C" fn unsafe_theory_atom_with_guard<T: GroundProgramObserver>(
         );
         return false;
     }
-    let elements = std::slice::from_raw_parts(elements as *const Id, size);
+    let elements = raw_slice(elements as *const Id, size);
     let gpo = &mut *(gpo as *mut T);
 
     gpo.theory_atom_with_guard(
@@ -6404,11 +7754,111 @@ impl ToSymbol for str {
         Symbol::create_string(self)
     }
 }
-impl<T: ToSymbol> ToSymbol for &T {
+impl<T: ToSymbol + ?Sized> ToSymbol for &T {
     fn symbol(&self) -> Result<Symbol, ClingoError> {
         (*self).symbol()
     }
 }
+impl<T: ToSymbol + ?Sized> ToSymbol for Box<T> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        (**self).symbol()
+    }
+}
+/// A `char` is represented as a string of length one.
+impl ToSymbol for char {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        Symbol::create_string(self.encode_utf8(&mut [0; 4]))
+    }
+}
+/// Floating point numbers are represented as strings, because clingo only supports integers.
+impl ToSymbol for f32 {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        Symbol::create_string(&self.to_string())
+    }
+}
+/// Floating point numbers are represented as strings, because clingo only supports integers.
+impl ToSymbol for f64 {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        Symbol::create_string(&self.to_string())
+    }
+}
+/// `None` is represented as `none` and `Some(x)` as `some(x)`.
+impl<T: ToSymbol> ToSymbol for Option<T> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        match self {
+            None => Symbol::create_id("none", true),
+            Some(x) => Symbol::create_function("some", &[x.symbol()?], true),
+        }
+    }
+}
+/// `Ok(x)` is represented as `ok(x)` and `Err(e)` as `error(e)`.
+impl<T: ToSymbol, E: ToSymbol> ToSymbol for Result<T, E> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        match self {
+            Ok(x) => Symbol::create_function("ok", &[x.symbol()?], true),
+            Err(e) => Symbol::create_function("error", &[e.symbol()?], true),
+        }
+    }
+}
+
+// Sequences are represented as tuples of their elements, e.g. `vec![1, 2, 3]` becomes `(1,2,3)`.
+// Sets and maps are represented as tuples of their elements respectively `(key,value)` pairs,
+// where elements of hashed collections are sorted to obtain a deterministic representation.
+fn sequence_symbol<T: ToSymbol>(elements: impl Iterator<Item = T>) -> Result<Symbol, ClingoError> {
+    let arguments = elements
+        .map(|x| x.symbol())
+        .collect::<Result<Vec<Symbol>, ClingoError>>()?;
+    Symbol::create_function("", &arguments, true)
+}
+fn sorted_sequence_symbol<T: ToSymbol>(
+    elements: impl Iterator<Item = T>,
+) -> Result<Symbol, ClingoError> {
+    let mut arguments = elements
+        .map(|x| x.symbol())
+        .collect::<Result<Vec<Symbol>, ClingoError>>()?;
+    arguments.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Symbol::create_function("", &arguments, true)
+}
+impl<T: ToSymbol> ToSymbol for [T] {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<T: ToSymbol, const N: usize> ToSymbol for [T; N] {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<T: ToSymbol> ToSymbol for Vec<T> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<T: ToSymbol> ToSymbol for VecDeque<T> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<T: ToSymbol> ToSymbol for BTreeSet<T> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<T: ToSymbol, S> ToSymbol for HashSet<T, S> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sorted_sequence_symbol(self.iter())
+    }
+}
+impl<K: ToSymbol, V: ToSymbol> ToSymbol for BTreeMap<K, V> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sequence_symbol(self.iter())
+    }
+}
+impl<K: ToSymbol, V: ToSymbol, S> ToSymbol for HashMap<K, V, S> {
+    fn symbol(&self) -> Result<Symbol, ClingoError> {
+        sorted_sequence_symbol(self.iter())
+    }
+}
 
 pub trait FromSymbol: Sized {
     type Error;
@@ -6509,12 +7959,254 @@ impl FromSymbol for String {
         Ok(symbol.string()?.into())
     }
 }
-impl FromSymbol for &'static str {
+impl FromSymbol for &str {
     type Error = ClingoError;
     fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
         symbol.string()
     }
 }
+impl FromSymbol for bool {
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        if !symbol.arguments()?.is_empty() || !symbol.is_positive()? {
+            return Err(ClingoError::new_external("Could not convert to bool"));
+        }
+        match symbol.name()? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(ClingoError::new_external("Could not convert to bool")),
+        }
+    }
+}
+impl FromSymbol for () {
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        if !symbol.name()?.is_empty() || !symbol.arguments()?.is_empty() {
+            return Err(ClingoError::new_external("Could not convert to ()"));
+        }
+        Ok(())
+    }
+}
+
+/// Check that `symbol` is a tuple of the given arity and return its arguments.
+fn tuple_arguments(symbol: Symbol, arity: usize) -> Result<Vec<Symbol>, ClingoError> {
+    let arguments = symbol.arguments()?;
+    if !symbol.name()?.is_empty() || arguments.len() != arity {
+        return Err(ClingoError::new_external("Could not convert to tuple"));
+    }
+    Ok(arguments)
+}
+
+// Due to a temporary restriction in Rust's type system, these function are only implemented on tuples of arity 12 or less.
+macro_rules! from_symbol_tuple {
+    ($arity:expr; $($name:ident $index:tt),+) => {
+        impl<$($name: FromSymbol),+> FromSymbol for ($($name,)+)
+        where
+            $(ClingoError: From<$name::Error>),+
+        {
+            type Error = ClingoError;
+            fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+                let arguments = tuple_arguments(symbol, $arity)?;
+                Ok(($($name::from_symbol(arguments[$index])?,)+))
+            }
+        }
+    };
+}
+from_symbol_tuple!(2; A 0, B 1);
+from_symbol_tuple!(3; A 0, B 1, C 2);
+from_symbol_tuple!(4; A 0, B 1, C 2, D 3);
+from_symbol_tuple!(5; A 0, B 1, C 2, D 3, E 4);
+from_symbol_tuple!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+from_symbol_tuple!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+from_symbol_tuple!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+from_symbol_tuple!(9; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+from_symbol_tuple!(10; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+from_symbol_tuple!(11; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+from_symbol_tuple!(12; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+impl<T: FromSymbol> FromSymbol for Box<T> {
+    type Error = T::Error;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        T::from_symbol(symbol).map(Box::new)
+    }
+}
+impl FromSymbol for char {
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        let mut chars = symbol.string()?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ClingoError::new_external("Could not convert to char")),
+        }
+    }
+}
+impl FromSymbol for f32 {
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        symbol
+            .string()?
+            .parse()
+            .map_err(|_| ClingoError::new_external("Could not convert to f32"))
+    }
+}
+impl FromSymbol for f64 {
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        symbol
+            .string()?
+            .parse()
+            .map_err(|_| ClingoError::new_external("Could not convert to f64"))
+    }
+}
+impl<T: FromSymbol> FromSymbol for Option<T>
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        let arguments = symbol.arguments()?;
+        match (symbol.name()?, arguments.as_slice()) {
+            ("none", []) => Ok(None),
+            ("some", [x]) => Ok(Some(T::from_symbol(*x)?)),
+            _ => Err(ClingoError::new_external("Could not convert to Option")),
+        }
+    }
+}
+impl<T: FromSymbol, E: FromSymbol> FromSymbol for Result<T, E>
+where
+    ClingoError: From<T::Error> + From<E::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        let arguments = symbol.arguments()?;
+        match (symbol.name()?, arguments.as_slice()) {
+            ("ok", [x]) => Ok(Ok(T::from_symbol(*x)?)),
+            ("error", [e]) => Ok(Err(E::from_symbol(*e)?)),
+            _ => Err(ClingoError::new_external("Could not convert to Result")),
+        }
+    }
+}
+
+/// Convert the elements of a tuple symbol.
+fn from_sequence_symbol<T: FromSymbol, C: FromIterator<T>>(symbol: Symbol) -> Result<C, ClingoError>
+where
+    ClingoError: From<T::Error>,
+{
+    if !symbol.name()?.is_empty() {
+        return Err(ClingoError::new_external("Could not convert to sequence"));
+    }
+    symbol
+        .arguments()?
+        .into_iter()
+        .map(|x| T::from_symbol(x).map_err(ClingoError::from))
+        .collect()
+}
+/// Convert the elements of a tuple symbol rejecting duplicate elements.
+fn from_set_symbol<T: FromSymbol, S: Default>(
+    symbol: Symbol,
+    insert: impl Fn(&mut S, T) -> bool,
+) -> Result<S, ClingoError>
+where
+    ClingoError: From<T::Error>,
+{
+    let elements: Vec<T> = from_sequence_symbol(symbol)?;
+    let mut set = S::default();
+    for element in elements {
+        if !insert(&mut set, element) {
+            return Err(ClingoError::new_external("Duplicate element in set"));
+        }
+    }
+    Ok(set)
+}
+/// Convert the key-value pairs of a tuple symbol rejecting duplicate keys.
+fn from_map_symbol<K: FromSymbol, V: FromSymbol, M: Default>(
+    symbol: Symbol,
+    insert: impl Fn(&mut M, K, V) -> Option<V>,
+) -> Result<M, ClingoError>
+where
+    ClingoError: From<K::Error> + From<V::Error>,
+{
+    let entries: Vec<(K, V)> = from_sequence_symbol(symbol)?;
+    let mut map = M::default();
+    for (key, value) in entries {
+        if insert(&mut map, key, value).is_some() {
+            return Err(ClingoError::new_external("Duplicate key in map"));
+        }
+    }
+    Ok(map)
+}
+impl<T: FromSymbol, const N: usize> FromSymbol for [T; N]
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        let elements: Vec<T> = from_sequence_symbol(symbol)?;
+        elements
+            .try_into()
+            .map_err(|_| ClingoError::new_external("Could not convert to array"))
+    }
+}
+impl<T: FromSymbol> FromSymbol for Vec<T>
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_sequence_symbol(symbol)
+    }
+}
+impl<T: FromSymbol> FromSymbol for VecDeque<T>
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_sequence_symbol(symbol)
+    }
+}
+impl<T: FromSymbol + Ord> FromSymbol for BTreeSet<T>
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_set_symbol(symbol, BTreeSet::insert)
+    }
+}
+impl<T: FromSymbol + Eq + Hash, S: BuildHasher + Default> FromSymbol for HashSet<T, S>
+where
+    ClingoError: From<T::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_set_symbol(symbol, HashSet::insert)
+    }
+}
+impl<K: FromSymbol + Ord, V: FromSymbol> FromSymbol for BTreeMap<K, V>
+where
+    ClingoError: From<K::Error> + From<V::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_map_symbol(symbol, BTreeMap::insert)
+    }
+}
+impl<K: FromSymbol + Eq + Hash, V: FromSymbol, S: BuildHasher + Default> FromSymbol
+    for HashMap<K, V, S>
+where
+    ClingoError: From<K::Error> + From<V::Error>,
+{
+    type Error = ClingoError;
+    fn from_symbol(symbol: Symbol) -> Result<Self, Self::Error> {
+        from_map_symbol(symbol, HashMap::insert)
+    }
+}
+
+/// A set of facts that can be added to a control object with [`Control::add_facts()`].
+///
+/// The facts are written in sorted order by [`FactBase::write_to()`] and the [`fmt::Display`]
+/// implementation, one fact per line, so that the output can be used as a `.lp` file.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct FactBase {
     facts: HashSet<Symbol>,
@@ -6534,20 +8226,71 @@ impl FactBase {
     pub fn iter(&self) -> std::collections::hash_set::Iter<'_, Symbol> {
         self.facts.iter()
     }
-    pub fn insert(&mut self, fact: &dyn ToSymbol) {
-        self.facts.insert(fact.symbol().unwrap());
-        // self.facts.sort();
+    /// Insert a fact, returns whether the fact was not present before.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError`] if the fact could not be converted to a symbol
+    pub fn insert(&mut self, fact: &dyn ToSymbol) -> Result<bool, ClingoError> {
+        Ok(self.facts.insert(fact.symbol()?))
+    }
+    /// Remove a fact, returns whether the fact was present.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError`] if the fact could not be converted to a symbol
+    pub fn remove(&mut self, fact: &dyn ToSymbol) -> Result<bool, ClingoError> {
+        Ok(self.facts.remove(&fact.symbol()?))
+    }
+    /// Check whether the fact base contains a fact.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError`] if the fact could not be converted to a symbol
+    pub fn contains(&self, fact: &dyn ToSymbol) -> Result<bool, ClingoError> {
+        Ok(self.facts.contains(&fact.symbol()?))
+    }
+    /// Get the facts with the given signature in sorted order.
+    pub fn by_signature(&self, signature: &Signature) -> Vec<Symbol> {
+        let mut facts: Vec<Symbol> = self
+            .facts
+            .iter()
+            .filter(|fact| fact.signature().is_ok_and(|s| s == *signature))
+            .copied()
+            .collect();
+        facts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        facts
     }
     pub fn union(&mut self, facts: &FactBase) {
         for s in &facts.facts {
             self.facts.insert(*s);
         }
     }
+    /// Get all facts in sorted order.
+    pub fn sorted(&self) -> Vec<Symbol> {
+        let mut facts: Vec<Symbol> = self.facts.iter().copied().collect();
+        facts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        facts
+    }
+    /// Write the facts in sorted order, one fact per line.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if writing fails
+    pub fn write_to<W: std::io::Write>(&self, mut out: W) -> std::io::Result<()> {
+        write!(out, "{self}")
+    }
+    /// Print the facts in sorted order to stdout.
     pub fn print(&self) {
-        for fact in &self.facts {
-            print!("{fact}.");
+        print!("{self}");
+    }
+}
+impl fmt::Display for FactBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for fact in self.sorted() {
+            writeln!(f, "{fact}.")?;
         }
-        println!();
+        Ok(())
     }
 }
 
@@ -6640,7 +8383,7 @@ impl FactBase {
 //     ) -> *const ::std::os::raw::c_char;
 // }
 
-// Re-export #[derive(ToSymbol)].
+// Re-export #[derive(ToSymbol)] and #[derive(FromSymbol)].
 #[cfg(feature = "derive")]
 #[allow(unused_imports)]
 pub use clingo_derive::*;
@@ -0,0 +1,805 @@
+use crate::{
+    Atom, Backend, ClingoError, ExternalType, GroundProgramObserver, HeuristicType, Id,
+    SolverLiteral, Symbol, TheorySequenceType, WeightedLiteral,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Writing and reading ground programs in the aspif format.
+pub mod aspif;
+
+/// A statement of a ground program as passed to a [`GroundProgramObserver`].
+///
+/// Atoms, literals and ids refer to the numbering of the control object that produced them.
+#[derive(Debug, Clone)]
+pub enum GroundStatement {
+    /// A disjunctive or choice rule, see [`GroundProgramObserver::rule()`].
+    Rule {
+        choice: bool,
+        head: Vec<Atom>,
+        body: Vec<SolverLiteral>,
+    },
+    /// A weight rule, see [`GroundProgramObserver::weight_rule()`].
+    WeightRule {
+        choice: bool,
+        head: Vec<Atom>,
+        lower_bound: i32,
+        body: Vec<WeightedLiteral>,
+    },
+    /// A minimize constraint, see [`GroundProgramObserver::minimize()`].
+    Minimize {
+        priority: i32,
+        literals: Vec<WeightedLiteral>,
+    },
+    /// A projection directive, see [`GroundProgramObserver::project()`].
+    Project { atoms: Vec<Atom> },
+    /// A shown atom, see [`GroundProgramObserver::output_atom()`].
+    OutputAtom { symbol: Symbol, atom: Atom },
+    /// A shown term, see [`GroundProgramObserver::output_term()`].
+    OutputTerm {
+        symbol: Symbol,
+        condition: Vec<SolverLiteral>,
+    },
+    /// An external statement, see [`GroundProgramObserver::external()`].
+    External {
+        atom: Atom,
+        external_type: ExternalType,
+    },
+    /// An assumption directive, see [`GroundProgramObserver::assume()`].
+    Assume { literals: Vec<SolverLiteral> },
+    /// A heuristic directive, see [`GroundProgramObserver::heuristic()`].
+    Heuristic {
+        atom: Atom,
+        heuristic_type: HeuristicType,
+        bias: i32,
+        priority: u32,
+        condition: Vec<SolverLiteral>,
+    },
+    /// An edge directive, see [`GroundProgramObserver::acyc_edge()`].
+    AcycEdge {
+        node_u: i32,
+        node_v: i32,
+        condition: Vec<SolverLiteral>,
+    },
+    /// A numeric theory term, see [`GroundProgramObserver::theory_term_number()`].
+    TheoryTermNumber { term_id: Id, number: i32 },
+    /// A string theory term, see [`GroundProgramObserver::theory_term_string()`].
+    TheoryTermString { term_id: Id, name: String },
+    /// A compound theory term, see [`GroundProgramObserver::theory_term_compound()`].
+    TheoryTermCompound {
+        term_id: Id,
+        name_id_or_type: i32,
+        arguments: Vec<Id>,
+    },
+    /// A theory element, see [`GroundProgramObserver::theory_element()`].
+    TheoryElement {
+        element_id: Id,
+        terms: Vec<Id>,
+        condition: Vec<SolverLiteral>,
+    },
+    /// A theory atom without guard, see [`GroundProgramObserver::theory_atom()`].
+    TheoryAtom {
+        atom_id_or_zero: Id,
+        term_id: Id,
+        elements: Vec<Id>,
+    },
+    /// A theory atom with guard, see [`GroundProgramObserver::theory_atom_with_guard()`].
+    TheoryAtomWithGuard {
+        atom_id_or_zero: Id,
+        term_id: Id,
+        elements: Vec<Id>,
+        operator_id: Id,
+        right_hand_side_id: Id,
+    },
+}
+
+/// The statements of a ground program passed to the solver in one step.
+#[derive(Debug, Clone, Default)]
+pub struct GroundProgram {
+    /// The statements in the order they were observed.
+    pub statements: Vec<GroundStatement>,
+}
+impl GroundProgram {
+    /// Add the statements to the given backend.
+    ///
+    /// See [`GroundProgramRecorder::replay()`] for how atoms are mapped.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the program contains a [`GroundStatement::OutputTerm`]
+    /// - [`ClingoError`] if a statement could not be added
+    pub fn replay(&self, backend: &mut Backend) -> Result<(), ClingoError> {
+        Replay::new(backend, [self]).run([self])
+    }
+}
+
+/// A ground program observer recording the ground program of each step.
+///
+/// The recorder shares its recordings with its clones, so that a clone can be registered with
+/// [`GenericControl::register_observer()`](crate::GenericControl::register_observer) while the
+/// original gives access to the recorded program.
+/// Recording can be stopped with [`GroundProgramRecorder::set_enabled()`], e.g., once the steps
+/// of interest have been grounded, because an observer cannot be unregistered.
+#[derive(Debug, Clone)]
+pub struct GroundProgramRecorder {
+    steps: Arc<Mutex<Vec<GroundProgram>>>,
+    enabled: Arc<AtomicBool>,
+}
+impl Default for GroundProgramRecorder {
+    fn default() -> Self {
+        GroundProgramRecorder {
+            steps: Arc::default(),
+            enabled: Arc::new(AtomicBool::new(true)),
+        }
+    }
+}
+impl GroundProgramRecorder {
+    /// Create a recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Enable or disable recording for the recorder and all its clones.
+    ///
+    /// Steps recorded so far are kept.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+    /// Whether the recorder records statements.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    /// Get the ground programs recorded so far, one per step.
+    pub fn steps(&self) -> Vec<GroundProgram> {
+        match self.steps.lock() {
+            Ok(steps) => steps.clone(),
+            Err(_) => vec![],
+        }
+    }
+    /// Remove all recorded steps.
+    pub fn clear(&self) {
+        if let Ok(mut steps) = self.steps.lock() {
+            steps.clear();
+        }
+    }
+    /// Add the statements of all recorded steps to the given backend.
+    ///
+    /// Atoms shown with a symbol are mapped to the backend's atoms for these symbols, and all
+    /// other atoms are mapped to fresh atoms.
+    /// Theory terms, elements and atoms are mapped likewise.
+    ///
+    /// **Note:** Programs with shown terms cannot be replayed because the backend of clingo 5.6
+    /// offers no directive to show terms.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if a program contains a [`GroundStatement::OutputTerm`]
+    /// - [`ClingoError`] if a statement could not be added
+    pub fn replay(&self, backend: &mut Backend) -> Result<(), ClingoError> {
+        let steps = self.steps();
+        Replay::new(backend, &steps).run(&steps)
+    }
+    fn record(&mut self, statement: GroundStatement) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        match self.steps.lock() {
+            Ok(mut steps) => {
+                if steps.is_empty() {
+                    steps.push(GroundProgram::default());
+                }
+                if let Some(step) = steps.last_mut() {
+                    step.statements.push(statement);
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+impl GroundProgramObserver for GroundProgramRecorder {
+    fn begin_step(&mut self) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        match self.steps.lock() {
+            Ok(mut steps) => {
+                steps.push(GroundProgram::default());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+    fn rule(&mut self, choice: bool, head: &[Atom], body: &[SolverLiteral]) -> bool {
+        self.record(GroundStatement::Rule {
+            choice,
+            head: head.to_vec(),
+            body: body.to_vec(),
+        })
+    }
+    fn weight_rule(
+        &mut self,
+        choice: bool,
+        head: &[Atom],
+        lower_bound: i32,
+        body: &[WeightedLiteral],
+    ) -> bool {
+        self.record(GroundStatement::WeightRule {
+            choice,
+            head: head.to_vec(),
+            lower_bound,
+            body: body.to_vec(),
+        })
+    }
+    fn minimize(&mut self, priority: i32, literals: &[WeightedLiteral]) -> bool {
+        self.record(GroundStatement::Minimize {
+            priority,
+            literals: literals.to_vec(),
+        })
+    }
+    fn project(&mut self, atoms: &[Atom]) -> bool {
+        self.record(GroundStatement::Project {
+            atoms: atoms.to_vec(),
+        })
+    }
+    fn output_atom(&mut self, symbol: Symbol, atom: Atom) -> bool {
+        self.record(GroundStatement::OutputAtom { symbol, atom })
+    }
+    fn output_term(&mut self, symbol: Symbol, condition: &[SolverLiteral]) -> bool {
+        self.record(GroundStatement::OutputTerm {
+            symbol,
+            condition: condition.to_vec(),
+        })
+    }
+    fn external(&mut self, atom: Atom, type_: ExternalType) -> bool {
+        self.record(GroundStatement::External {
+            atom,
+            external_type: type_,
+        })
+    }
+    fn assume(&mut self, literals: &[SolverLiteral]) -> bool {
+        self.record(GroundStatement::Assume {
+            literals: literals.to_vec(),
+        })
+    }
+    fn heuristic(
+        &mut self,
+        atom: Atom,
+        type_: HeuristicType,
+        bias: i32,
+        priority: u32,
+        condition: &[SolverLiteral],
+    ) -> bool {
+        self.record(GroundStatement::Heuristic {
+            atom,
+            heuristic_type: type_,
+            bias,
+            priority,
+            condition: condition.to_vec(),
+        })
+    }
+    fn acyc_edge(&mut self, node_u: i32, node_v: i32, condition: &[SolverLiteral]) -> bool {
+        self.record(GroundStatement::AcycEdge {
+            node_u,
+            node_v,
+            condition: condition.to_vec(),
+        })
+    }
+    fn theory_term_number(&mut self, term_id: Id, number: i32) -> bool {
+        self.record(GroundStatement::TheoryTermNumber { term_id, number })
+    }
+    fn theory_term_string(&mut self, term_id: Id, name: &str) -> bool {
+        self.record(GroundStatement::TheoryTermString {
+            term_id,
+            name: name.to_owned(),
+        })
+    }
+    fn theory_term_compound(
+        &mut self,
+        term_id: Id,
+        name_id_or_type: i32,
+        arguments: &[Id],
+    ) -> bool {
+        self.record(GroundStatement::TheoryTermCompound {
+            term_id,
+            name_id_or_type,
+            arguments: arguments.to_vec(),
+        })
+    }
+    fn theory_element(
+        &mut self,
+        element_id: Id,
+        terms: &[Id],
+        condition: &[SolverLiteral],
+    ) -> bool {
+        self.record(GroundStatement::TheoryElement {
+            element_id,
+            terms: terms.to_vec(),
+            condition: condition.to_vec(),
+        })
+    }
+    fn theory_atom(&mut self, atom_id_or_zero: Id, term_id: Id, elements: &[Id]) -> bool {
+        self.record(GroundStatement::TheoryAtom {
+            atom_id_or_zero,
+            term_id,
+            elements: elements.to_vec(),
+        })
+    }
+    fn theory_atom_with_guard(
+        &mut self,
+        atom_id_or_zero: Id,
+        term_id: Id,
+        elements: &[Id],
+        operator_id: Id,
+        right_hand_side_id: Id,
+    ) -> bool {
+        self.record(GroundStatement::TheoryAtomWithGuard {
+            atom_id_or_zero,
+            term_id,
+            elements: elements.to_vec(),
+            operator_id,
+            right_hand_side_id,
+        })
+    }
+}
+
+/// A ground program observer printing rules, weight rules, minimize constraints and externals
+/// in a human-readable text format.
+///
+/// Atoms are printed as the symbols they are shown as and as `#aux(n)` otherwise.
+/// Because shown atoms are only known at the end of a step, the statements of a step are
+/// printed and the writer is flushed when the step ends.
+///
+/// ```text
+/// p(1).
+/// {q(1);q(2)}.
+/// r :- q(1), not q(2).
+/// :- 3 <= #sum { 1,q(1): q(1); 1,q(2): q(2) }.
+/// #minimize { 1@0,q(1): q(1); 1@0,q(2): q(2) }.
+/// #external e. [false]
+/// ```
+#[derive(Debug)]
+pub struct GroundProgramPrinter<W: Write> {
+    writer: W,
+    symbols: HashMap<u32, Symbol>,
+    statements: Vec<GroundStatement>,
+}
+impl<W: Write> GroundProgramPrinter<W> {
+    /// Create an observer printing to the given writer.
+    pub fn new(writer: W) -> GroundProgramPrinter<W> {
+        GroundProgramPrinter {
+            writer,
+            symbols: HashMap::new(),
+            statements: vec![],
+        }
+    }
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+    fn atom(&self, Atom(atom): Atom) -> String {
+        match self.symbols.get(&atom) {
+            Some(symbol) => symbol.to_string(),
+            None => format!("#aux({atom})"),
+        }
+    }
+    fn literal(&self, SolverLiteral(literal): SolverLiteral) -> String {
+        let atom = self.atom(Atom(literal.unsigned_abs()));
+        if literal < 0 {
+            format!("not {atom}")
+        } else {
+            atom
+        }
+    }
+    fn head(&self, choice: bool, head: &[Atom]) -> String {
+        let atoms: Vec<String> = head.iter().map(|atom| self.atom(*atom)).collect();
+        if choice {
+            format!("{{{}}}", atoms.join(";"))
+        } else {
+            atoms.join(";")
+        }
+    }
+    /// Print the elements of an aggregate, the tuples contain the literals to keep elements
+    /// with the same weight apart like `clingo --text`.
+    fn weighted_literals(&self, literals: &[WeightedLiteral], priority: Option<i32>) -> String {
+        let elements: Vec<String> = literals
+            .iter()
+            .map(|literal| {
+                let weight = match priority {
+                    Some(priority) => format!("{}@{priority}", literal.weight()),
+                    None => literal.weight().to_string(),
+                };
+                let literal = self.literal(literal.literal());
+                format!("{weight},{literal}: {literal}")
+            })
+            .collect();
+        elements.join("; ")
+    }
+    fn rule(&self, head: String, body: &[String]) -> String {
+        match (head.is_empty(), body.is_empty()) {
+            (true, true) => ":- .".to_owned(),
+            (false, true) => format!("{head}."),
+            (true, false) => format!(":- {}.", body.join(", ")),
+            (false, false) => format!("{head} :- {}.", body.join(", ")),
+        }
+    }
+    fn statement(&self, statement: &GroundStatement) -> Option<String> {
+        match statement {
+            GroundStatement::Rule { choice, head, body } => {
+                let body: Vec<String> = body.iter().map(|literal| self.literal(*literal)).collect();
+                Some(self.rule(self.head(*choice, head), &body))
+            }
+            GroundStatement::WeightRule {
+                choice,
+                head,
+                lower_bound,
+                body,
+            } => {
+                let body = format!(
+                    "{lower_bound} <= #sum {{ {} }}",
+                    self.weighted_literals(body, None)
+                );
+                Some(self.rule(self.head(*choice, head), &[body]))
+            }
+            GroundStatement::Minimize { priority, literals } => Some(format!(
+                "#minimize {{ {} }}.",
+                self.weighted_literals(literals, Some(*priority))
+            )),
+            GroundStatement::External {
+                atom,
+                external_type,
+            } => {
+                let value = match external_type {
+                    ExternalType::Free => "free",
+                    ExternalType::True => "true",
+                    ExternalType::False => "false",
+                    ExternalType::Release => "release",
+                };
+                Some(format!("#external {}. [{value}]", self.atom(*atom)))
+            }
+            GroundStatement::OutputAtom {
+                symbol,
+                atom: Atom(0),
+            } => Some(format!("{symbol}.")),
+            _ => None,
+        }
+    }
+}
+impl<W: Write> GroundProgramObserver for GroundProgramPrinter<W> {
+    fn end_step(&mut self) -> bool {
+        let statements = std::mem::take(&mut self.statements);
+        for statement in &statements {
+            if let Some(line) = self.statement(statement) {
+                if writeln!(self.writer, "{line}").is_err() {
+                    return false;
+                }
+            }
+        }
+        self.writer.flush().is_ok()
+    }
+    fn rule(&mut self, choice: bool, head: &[Atom], body: &[SolverLiteral]) -> bool {
+        self.statements.push(GroundStatement::Rule {
+            choice,
+            head: head.to_vec(),
+            body: body.to_vec(),
+        });
+        true
+    }
+    fn weight_rule(
+        &mut self,
+        choice: bool,
+        head: &[Atom],
+        lower_bound: i32,
+        body: &[WeightedLiteral],
+    ) -> bool {
+        self.statements.push(GroundStatement::WeightRule {
+            choice,
+            head: head.to_vec(),
+            lower_bound,
+            body: body.to_vec(),
+        });
+        true
+    }
+    fn minimize(&mut self, priority: i32, literals: &[WeightedLiteral]) -> bool {
+        self.statements.push(GroundStatement::Minimize {
+            priority,
+            literals: literals.to_vec(),
+        });
+        true
+    }
+    fn output_atom(&mut self, symbol: Symbol, atom: Atom) -> bool {
+        if atom.0 == 0 {
+            self.statements
+                .push(GroundStatement::OutputAtom { symbol, atom });
+        } else {
+            self.symbols.entry(atom.0).or_insert(symbol);
+        }
+        true
+    }
+    fn external(&mut self, atom: Atom, type_: ExternalType) -> bool {
+        self.statements.push(GroundStatement::External {
+            atom,
+            external_type: type_,
+        });
+        true
+    }
+}
+
+/// The backend of clingo 5.6 has no directive to show terms.
+fn output_term_error() -> ClingoError {
+    ClingoError::new_external("Shown terms cannot be added to a backend")
+}
+
+/// Maps the atoms, terms and elements of recorded statements to those of a backend.
+struct Replay<'b, 'a> {
+    backend: &'b mut Backend<'a>,
+    symbols: HashMap<u32, Symbol>,
+    atoms: HashMap<u32, Atom>,
+    strings: HashMap<u32, String>,
+    terms: HashMap<u32, Id>,
+    elements: HashMap<u32, Id>,
+}
+impl<'b, 'a> Replay<'b, 'a> {
+    fn new<'p>(
+        backend: &'b mut Backend<'a>,
+        programs: impl IntoIterator<Item = &'p GroundProgram>,
+    ) -> Self {
+        let mut symbols = HashMap::new();
+        for program in programs {
+            for statement in &program.statements {
+                if let GroundStatement::OutputAtom { symbol, atom } = statement {
+                    if atom.0 != 0 {
+                        symbols.entry(atom.0).or_insert(*symbol);
+                    }
+                }
+            }
+        }
+        Replay {
+            backend,
+            symbols,
+            atoms: HashMap::new(),
+            strings: HashMap::new(),
+            terms: HashMap::new(),
+            elements: HashMap::new(),
+        }
+    }
+    fn atom(&mut self, Atom(atom): Atom) -> Result<Atom, ClingoError> {
+        if let Some(mapped) = self.atoms.get(&atom) {
+            return Ok(*mapped);
+        }
+        let mapped = self.backend.add_atom(self.symbols.get(&atom).copied())?;
+        self.atoms.insert(atom, mapped);
+        Ok(mapped)
+    }
+    fn atoms(&mut self, atoms: &[Atom]) -> Result<Vec<Atom>, ClingoError> {
+        atoms.iter().map(|atom| self.atom(*atom)).collect()
+    }
+    fn literal(&mut self, literal: SolverLiteral) -> Result<SolverLiteral, ClingoError> {
+        let mapped = SolverLiteral::from(self.atom(Atom(literal.0.unsigned_abs()))?);
+        if literal.0 < 0 {
+            Ok(mapped.negate())
+        } else {
+            Ok(mapped)
+        }
+    }
+    fn literals(&mut self, literals: &[SolverLiteral]) -> Result<Vec<SolverLiteral>, ClingoError> {
+        literals
+            .iter()
+            .map(|literal| self.literal(*literal))
+            .collect()
+    }
+    fn weighted_literals(
+        &mut self,
+        literals: &[WeightedLiteral],
+    ) -> Result<Vec<WeightedLiteral>, ClingoError> {
+        literals
+            .iter()
+            .map(|literal| {
+                Ok(WeightedLiteral::new(
+                    self.literal(literal.literal())?,
+                    literal.weight(),
+                ))
+            })
+            .collect()
+    }
+    fn theory_atom(&mut self, Id(atom): Id) -> Result<Option<Atom>, ClingoError> {
+        match atom {
+            0 => Ok(None),
+            atom => Ok(Some(self.atom(Atom(atom))?)),
+        }
+    }
+    fn term(&self, Id(term): Id) -> Result<Id, ClingoError> {
+        self.terms
+            .get(&term)
+            .copied()
+            .ok_or(ClingoError::new_external("Unknown theory term"))
+    }
+    fn terms(&self, terms: &[Id]) -> Result<Vec<Id>, ClingoError> {
+        terms.iter().map(|term| self.term(*term)).collect()
+    }
+    fn string(&self, Id(term): Id) -> Result<&str, ClingoError> {
+        self.strings
+            .get(&term)
+            .map(String::as_str)
+            .ok_or(ClingoError::new_external("Unknown theory string term"))
+    }
+    fn elements(&self, elements: &[Id]) -> Result<Vec<Id>, ClingoError> {
+        elements
+            .iter()
+            .map(|Id(element)| {
+                self.elements
+                    .get(element)
+                    .copied()
+                    .ok_or(ClingoError::new_external("Unknown theory element"))
+            })
+            .collect()
+    }
+    fn run<'p>(
+        mut self,
+        programs: impl IntoIterator<Item = &'p GroundProgram>,
+    ) -> Result<(), ClingoError> {
+        let programs: Vec<_> = programs.into_iter().collect();
+        let statements = programs.iter().flat_map(|program| &program.statements);
+        // checked upfront to leave the backend unchanged
+        if statements
+            .clone()
+            .any(|statement| matches!(statement, GroundStatement::OutputTerm { .. }))
+        {
+            return Err(output_term_error());
+        }
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        Ok(())
+    }
+    fn statement(&mut self, statement: &GroundStatement) -> Result<(), ClingoError> {
+        match statement {
+            GroundStatement::Rule { choice, head, body } => {
+                let head = self.atoms(head)?;
+                let body = self.literals(body)?;
+                self.backend.rule(*choice, &head, &body)
+            }
+            GroundStatement::WeightRule {
+                choice,
+                head,
+                lower_bound,
+                body,
+            } => {
+                let head = self.atoms(head)?;
+                let body = self.weighted_literals(body)?;
+                self.backend
+                    .weight_rule(*choice, &head, *lower_bound, &body)
+            }
+            GroundStatement::Minimize { priority, literals } => {
+                let literals = self.weighted_literals(literals)?;
+                self.backend.minimize(*priority, &literals)
+            }
+            GroundStatement::Project { atoms } => {
+                let atoms = self.atoms(atoms)?;
+                self.backend.project(&atoms)
+            }
+            GroundStatement::OutputAtom { symbol, atom } => {
+                if atom.0 == 0 {
+                    // facts are shown with atom zero
+                    let atom = self.backend.add_atom(Some(*symbol))?;
+                    self.backend.rule(false, &[atom], &[])
+                } else {
+                    self.atom(*atom).map(|_| ())
+                }
+            }
+            GroundStatement::OutputTerm { .. } => Err(output_term_error()),
+            GroundStatement::External {
+                atom,
+                external_type,
+            } => {
+                let atom = self.atom(*atom)?;
+                self.backend.external(atom, *external_type)
+            }
+            GroundStatement::Assume { literals } => {
+                let literals = self.literals(literals)?;
+                self.backend.assume(&literals)
+            }
+            GroundStatement::Heuristic {
+                atom,
+                heuristic_type,
+                bias,
+                priority,
+                condition,
+            } => {
+                let atom = self.atom(*atom)?;
+                let condition = self.literals(condition)?;
+                self.backend
+                    .heuristic(atom, *heuristic_type, *bias, *priority, &condition)
+            }
+            GroundStatement::AcycEdge {
+                node_u,
+                node_v,
+                condition,
+            } => {
+                let condition = self.literals(condition)?;
+                self.backend.acyc_edge(*node_u, *node_v, &condition)
+            }
+            GroundStatement::TheoryTermNumber { term_id, number } => {
+                let term = self.backend.theory_term_number(*number)?;
+                self.terms.insert(term_id.0, term);
+                Ok(())
+            }
+            GroundStatement::TheoryTermString { term_id, name } => {
+                let term = self.backend.theory_term_string(name)?;
+                self.terms.insert(term_id.0, term);
+                self.strings.insert(term_id.0, name.clone());
+                Ok(())
+            }
+            GroundStatement::TheoryTermCompound {
+                term_id,
+                name_id_or_type,
+                arguments,
+            } => {
+                let arguments = self.terms(arguments)?;
+                let term = match name_id_or_type {
+                    -1 => self
+                        .backend
+                        .theory_term_sequence(TheorySequenceType::Tuple, &arguments)?,
+                    -2 => self
+                        .backend
+                        .theory_term_sequence(TheorySequenceType::Set, &arguments)?,
+                    -3 => self
+                        .backend
+                        .theory_term_sequence(TheorySequenceType::List, &arguments)?,
+                    name => {
+                        let name = self.string(Id(*name as u32))?.to_owned();
+                        self.backend.theory_term_function(&name, &arguments)?
+                    }
+                };
+                self.terms.insert(term_id.0, term);
+                Ok(())
+            }
+            GroundStatement::TheoryElement {
+                element_id,
+                terms,
+                condition,
+            } => {
+                let terms = self.terms(terms)?;
+                let condition = self.literals(condition)?;
+                let element = self.backend.theory_element(&terms, &condition)?;
+                self.elements.insert(element_id.0, element);
+                Ok(())
+            }
+            GroundStatement::TheoryAtom {
+                atom_id_or_zero,
+                term_id,
+                elements,
+            } => {
+                let atom = self.theory_atom(*atom_id_or_zero)?;
+                let term = self.term(*term_id)?;
+                let elements = self.elements(elements)?;
+                self.backend.theory_atom(atom, term, &elements)
+            }
+            GroundStatement::TheoryAtomWithGuard {
+                atom_id_or_zero,
+                term_id,
+                elements,
+                operator_id,
+                right_hand_side_id,
+            } => {
+                let atom = self.theory_atom(*atom_id_or_zero)?;
+                let term = self.term(*term_id)?;
+                let elements = self.elements(elements)?;
+                let operator = self.string(*operator_id)?.to_owned();
+                let right_hand_side = self.term(*right_hand_side_id)?;
+                self.backend.theory_atom_with_guard(
+                    atom,
+                    term,
+                    &elements,
+                    &operator,
+                    right_hand_side,
+                )
+            }
+        }
+    }
+}
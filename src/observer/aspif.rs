@@ -0,0 +1,432 @@
+use super::{GroundProgram, GroundStatement, Replay};
+use crate::{
+    parse_term, Atom, Backend, ClingoError, ExternalType, GroundProgramObserver, HeuristicType, Id,
+    SolverLiteral, Symbol, WeightedLiteral,
+};
+use std::fmt::{Display, Write as _};
+use std::io::Write;
+use std::str::FromStr;
+
+/// A line of the aspif format under construction.
+struct Line(String);
+impl Line {
+    fn new(statement: u32) -> Line {
+        Line(statement.to_string())
+    }
+    fn number(mut self, number: impl Display) -> Line {
+        let _ = write!(self.0, " {number}");
+        self
+    }
+    fn string(self, string: &str) -> Line {
+        self.number(string.len()).number(string)
+    }
+    fn atoms(self, atoms: &[Atom]) -> Line {
+        atoms
+            .iter()
+            .fold(self.number(atoms.len()), |line, Atom(atom)| {
+                line.number(atom)
+            })
+    }
+    fn literals(self, literals: &[SolverLiteral]) -> Line {
+        literals.iter().fold(
+            self.number(literals.len()),
+            |line, SolverLiteral(literal)| line.number(literal),
+        )
+    }
+    fn weighted_literals(self, literals: &[WeightedLiteral]) -> Line {
+        literals
+            .iter()
+            .fold(self.number(literals.len()), |line, literal| {
+                line.number(literal.literal().0).number(literal.weight())
+            })
+    }
+    fn ids(self, ids: &[Id]) -> Line {
+        ids.iter()
+            .fold(self.number(ids.len()), |line, Id(id)| line.number(id))
+    }
+}
+
+/// A ground program observer writing the ground program in the aspif format.
+///
+/// Each step is terminated by a `0` line, so that incremental programs can be read back with
+/// [`parse()`] step by step.
+/// The writer is flushed at the end of each step.
+///
+/// **Note:** Symbols are written in their string representation.
+#[derive(Debug)]
+pub struct AspifWriter<W: Write> {
+    writer: W,
+}
+impl<W: Write> AspifWriter<W> {
+    /// Create an observer writing to the given writer.
+    pub fn new(writer: W) -> AspifWriter<W> {
+        AspifWriter { writer }
+    }
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+    fn write(&mut self, Line(line): Line) -> bool {
+        writeln!(self.writer, "{line}").is_ok()
+    }
+}
+impl<W: Write> GroundProgramObserver for AspifWriter<W> {
+    fn init_program(&mut self, incremental: bool) -> bool {
+        if incremental {
+            writeln!(self.writer, "asp 1 0 0 incremental").is_ok()
+        } else {
+            writeln!(self.writer, "asp 1 0 0").is_ok()
+        }
+    }
+    fn end_step(&mut self) -> bool {
+        writeln!(self.writer, "0").is_ok() && self.writer.flush().is_ok()
+    }
+    fn rule(&mut self, choice: bool, head: &[Atom], body: &[SolverLiteral]) -> bool {
+        self.write(
+            Line::new(1)
+                .number(u32::from(choice))
+                .atoms(head)
+                .number(0)
+                .literals(body),
+        )
+    }
+    fn weight_rule(
+        &mut self,
+        choice: bool,
+        head: &[Atom],
+        lower_bound: i32,
+        body: &[WeightedLiteral],
+    ) -> bool {
+        self.write(
+            Line::new(1)
+                .number(u32::from(choice))
+                .atoms(head)
+                .number(1)
+                .number(lower_bound)
+                .weighted_literals(body),
+        )
+    }
+    fn minimize(&mut self, priority: i32, literals: &[WeightedLiteral]) -> bool {
+        self.write(Line::new(2).number(priority).weighted_literals(literals))
+    }
+    fn project(&mut self, atoms: &[Atom]) -> bool {
+        self.write(Line::new(3).atoms(atoms))
+    }
+    fn output_atom(&mut self, symbol: Symbol, Atom(atom): Atom) -> bool {
+        let line = Line::new(4).string(&symbol.to_string());
+        if atom == 0 {
+            self.write(line.number(0))
+        } else {
+            self.write(line.number(1).number(atom))
+        }
+    }
+    fn output_term(&mut self, symbol: Symbol, condition: &[SolverLiteral]) -> bool {
+        self.write(Line::new(4).string(&symbol.to_string()).literals(condition))
+    }
+    fn external(&mut self, Atom(atom): Atom, type_: ExternalType) -> bool {
+        self.write(Line::new(5).number(atom).number(type_ as u32))
+    }
+    fn assume(&mut self, literals: &[SolverLiteral]) -> bool {
+        self.write(Line::new(6).literals(literals))
+    }
+    fn heuristic(
+        &mut self,
+        Atom(atom): Atom,
+        type_: HeuristicType,
+        bias: i32,
+        priority: u32,
+        condition: &[SolverLiteral],
+    ) -> bool {
+        self.write(
+            Line::new(7)
+                .number(type_ as u32)
+                .number(atom)
+                .number(bias)
+                .number(priority)
+                .literals(condition),
+        )
+    }
+    fn acyc_edge(&mut self, node_u: i32, node_v: i32, condition: &[SolverLiteral]) -> bool {
+        self.write(
+            Line::new(8)
+                .number(node_u)
+                .number(node_v)
+                .literals(condition),
+        )
+    }
+    fn theory_term_number(&mut self, Id(term_id): Id, number: i32) -> bool {
+        self.write(Line::new(9).number(0).number(term_id).number(number))
+    }
+    fn theory_term_string(&mut self, Id(term_id): Id, name: &str) -> bool {
+        self.write(Line::new(9).number(1).number(term_id).string(name))
+    }
+    fn theory_term_compound(
+        &mut self,
+        Id(term_id): Id,
+        name_id_or_type: i32,
+        arguments: &[Id],
+    ) -> bool {
+        self.write(
+            Line::new(9)
+                .number(2)
+                .number(term_id)
+                .number(name_id_or_type)
+                .ids(arguments),
+        )
+    }
+    fn theory_element(
+        &mut self,
+        Id(element_id): Id,
+        terms: &[Id],
+        condition: &[SolverLiteral],
+    ) -> bool {
+        self.write(
+            Line::new(9)
+                .number(4)
+                .number(element_id)
+                .ids(terms)
+                .literals(condition),
+        )
+    }
+    fn theory_atom(&mut self, Id(atom_id_or_zero): Id, Id(term_id): Id, elements: &[Id]) -> bool {
+        self.write(
+            Line::new(9)
+                .number(5)
+                .number(atom_id_or_zero)
+                .number(term_id)
+                .ids(elements),
+        )
+    }
+    fn theory_atom_with_guard(
+        &mut self,
+        Id(atom_id_or_zero): Id,
+        Id(term_id): Id,
+        elements: &[Id],
+        Id(operator_id): Id,
+        Id(right_hand_side_id): Id,
+    ) -> bool {
+        self.write(
+            Line::new(9)
+                .number(6)
+                .number(atom_id_or_zero)
+                .number(term_id)
+                .ids(elements)
+                .number(operator_id)
+                .number(right_hand_side_id),
+        )
+    }
+}
+
+fn invalid_statement() -> ClingoError {
+    ClingoError::new_external("Invalid aspif statement")
+}
+
+/// The remaining tokens of an aspif line.
+struct Tokens<'l> {
+    rest: &'l str,
+}
+impl<'l> Tokens<'l> {
+    fn token(&mut self) -> Result<&'l str, ClingoError> {
+        let rest = self.rest.trim_start_matches(' ');
+        let end = rest.find(' ').unwrap_or(rest.len());
+        if end == 0 {
+            return Err(invalid_statement());
+        }
+        let (token, rest) = rest.split_at(end);
+        self.rest = rest;
+        Ok(token)
+    }
+    fn number<T: FromStr>(&mut self) -> Result<T, ClingoError> {
+        self.token()?.parse().map_err(|_| invalid_statement())
+    }
+    fn string(&mut self) -> Result<&'l str, ClingoError> {
+        let len: usize = self.number()?;
+        let string = self
+            .rest
+            .strip_prefix(' ')
+            .and_then(|rest| rest.get(..len))
+            .ok_or_else(invalid_statement)?;
+        self.rest = &self.rest[len + 1..];
+        Ok(string)
+    }
+    fn list<T>(
+        &mut self,
+        mut element: impl FnMut(&mut Tokens<'l>) -> Result<T, ClingoError>,
+    ) -> Result<Vec<T>, ClingoError> {
+        let len: usize = self.number()?;
+        (0..len).map(|_| element(self)).collect()
+    }
+    fn atom(&mut self) -> Result<Atom, ClingoError> {
+        self.number().map(Atom)
+    }
+    fn id(&mut self) -> Result<Id, ClingoError> {
+        self.number().map(Id)
+    }
+    fn literal(&mut self) -> Result<SolverLiteral, ClingoError> {
+        self.number().map(SolverLiteral)
+    }
+    fn weighted_literal(&mut self) -> Result<WeightedLiteral, ClingoError> {
+        Ok(WeightedLiteral::new(self.literal()?, self.number()?))
+    }
+    fn end(&self) -> Result<(), ClingoError> {
+        if self.rest.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_statement())
+        }
+    }
+}
+
+fn parse_statement(tokens: &mut Tokens) -> Result<Option<GroundStatement>, ClingoError> {
+    let statement = match tokens.number::<u32>()? {
+        1 => {
+            let choice = tokens.number::<u32>()? == 1;
+            let head = tokens.list(Tokens::atom)?;
+            match tokens.number::<u32>()? {
+                0 => GroundStatement::Rule {
+                    choice,
+                    head,
+                    body: tokens.list(Tokens::literal)?,
+                },
+                1 => GroundStatement::WeightRule {
+                    choice,
+                    head,
+                    lower_bound: tokens.number()?,
+                    body: tokens.list(Tokens::weighted_literal)?,
+                },
+                _ => return Err(invalid_statement()),
+            }
+        }
+        2 => GroundStatement::Minimize {
+            priority: tokens.number()?,
+            literals: tokens.list(Tokens::weighted_literal)?,
+        },
+        3 => GroundStatement::Project {
+            atoms: tokens.list(Tokens::atom)?,
+        },
+        4 => {
+            let symbol = parse_term(tokens.string()?)?;
+            let condition = tokens.list(Tokens::literal)?;
+            match condition[..] {
+                [] => GroundStatement::OutputAtom {
+                    symbol,
+                    atom: Atom(0),
+                },
+                [SolverLiteral(literal)] if literal > 0 => GroundStatement::OutputAtom {
+                    symbol,
+                    atom: Atom(literal as u32),
+                },
+                _ => GroundStatement::OutputTerm { symbol, condition },
+            }
+        }
+        5 => GroundStatement::External {
+            atom: tokens.atom()?,
+            external_type: ExternalType::try_from(tokens.number()?)
+                .map_err(|_| invalid_statement())?,
+        },
+        6 => GroundStatement::Assume {
+            literals: tokens.list(Tokens::literal)?,
+        },
+        7 => GroundStatement::Heuristic {
+            heuristic_type: HeuristicType::try_from(tokens.number()?)
+                .map_err(|_| invalid_statement())?,
+            atom: tokens.atom()?,
+            bias: tokens.number()?,
+            priority: tokens.number()?,
+            condition: tokens.list(Tokens::literal)?,
+        },
+        8 => GroundStatement::AcycEdge {
+            node_u: tokens.number()?,
+            node_v: tokens.number()?,
+            condition: tokens.list(Tokens::literal)?,
+        },
+        9 => match tokens.number::<u32>()? {
+            0 => GroundStatement::TheoryTermNumber {
+                term_id: tokens.id()?,
+                number: tokens.number()?,
+            },
+            1 => GroundStatement::TheoryTermString {
+                term_id: tokens.id()?,
+                name: tokens.string()?.to_owned(),
+            },
+            2 => GroundStatement::TheoryTermCompound {
+                term_id: tokens.id()?,
+                name_id_or_type: tokens.number()?,
+                arguments: tokens.list(Tokens::id)?,
+            },
+            4 => GroundStatement::TheoryElement {
+                element_id: tokens.id()?,
+                terms: tokens.list(Tokens::id)?,
+                condition: tokens.list(Tokens::literal)?,
+            },
+            5 => GroundStatement::TheoryAtom {
+                atom_id_or_zero: tokens.id()?,
+                term_id: tokens.id()?,
+                elements: tokens.list(Tokens::id)?,
+            },
+            6 => GroundStatement::TheoryAtomWithGuard {
+                atom_id_or_zero: tokens.id()?,
+                term_id: tokens.id()?,
+                elements: tokens.list(Tokens::id)?,
+                operator_id: tokens.id()?,
+                right_hand_side_id: tokens.id()?,
+            },
+            _ => return Err(invalid_statement()),
+        },
+        // comments
+        10 => return Ok(None),
+        _ => return Err(invalid_statement()),
+    };
+    tokens.end()?;
+    Ok(Some(statement))
+}
+
+/// Parse a ground program in the aspif format.
+///
+/// **Returns** the ground programs of the steps, each terminated by a `0` line
+///
+/// Output statements with an empty condition or a single positive literal as condition are
+/// parsed as [`GroundStatement::OutputAtom`], all others as [`GroundStatement::OutputTerm`].
+///
+/// # Errors
+///
+/// - [`ClingoError::ExternalError`] if the header or a statement is invalid
+/// - [`ClingoError::InternalError`] if a symbol could not be parsed
+pub fn parse(input: &str) -> Result<Vec<GroundProgram>, ClingoError> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+    match lines.next() {
+        Some(header) if header.starts_with("asp 1 ") => {}
+        _ => return Err(ClingoError::new_external("Invalid aspif header")),
+    }
+    let mut programs = vec![];
+    let mut program = GroundProgram::default();
+    for line in lines {
+        if line.trim() == "0" {
+            programs.push(std::mem::take(&mut program));
+        } else if let Some(statement) = parse_statement(&mut Tokens { rest: line })? {
+            program.statements.push(statement);
+        }
+    }
+    if !program.statements.is_empty() {
+        programs.push(program);
+    }
+    Ok(programs)
+}
+
+/// Parse a ground program in the aspif format and add it to the given backend.
+///
+/// Atoms are mapped like in [`GroundProgramRecorder::replay()`](super::GroundProgramRecorder::replay).
+///
+/// # Errors
+///
+/// - [`ClingoError::ExternalError`] if the program contains output statements parsed as
+///   [`GroundStatement::OutputTerm`], which cannot be added to a backend
+/// - [`ClingoError`] if the program could not be parsed or a statement could not be added
+pub fn read(input: &str, backend: &mut Backend) -> Result<(), ClingoError> {
+    let programs = parse(input)?;
+    Replay::new(backend, &programs).run(&programs)
+}
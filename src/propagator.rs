@@ -0,0 +1,784 @@
+use crate::{
+    report_error, Assignment, ClauseType, ClingoError, Id, PropagateControl, PropagateInit,
+    Propagator, Signature, SolverLiteral, Symbol, TruthValue,
+};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+/// The state of a propagator for a single solver thread.
+///
+/// See [`ThreadLocalPropagator`].
+pub trait ThreadState: Sized {
+    /// Create the state for the solver thread with the given id.
+    ///
+    /// This function is called once per solver thread before each solving step.
+    /// Watches should be added with [`PropagateInit::add_watch_to_thread()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - initizialization object
+    /// * `thread_id` - the solver's thread id
+    fn init(init: &mut PropagateInit, thread_id: u32) -> Result<Self, ClingoError>;
+
+    /// Propagate the changes of the thread's assignment, see [`Propagator::propagate()`].
+    ///
+    /// **Returns** whether the call was successful
+    fn propagate(&mut self, _control: &mut PropagateControl, _changes: &[SolverLiteral]) -> bool {
+        true
+    }
+    /// Undo the changes of the thread's assignment, see [`Propagator::undo()`].
+    fn undo(&mut self, _control: &mut PropagateControl, _changes: &[SolverLiteral]) {}
+
+    /// Check the thread's assignment, see [`Propagator::check()`].
+    ///
+    /// **Returns** whether the call was successful
+    fn check(&mut self, _control: &mut PropagateControl) -> bool {
+        true
+    }
+    /// Make a decision for the thread, see [`Propagator::decide()`].
+    ///
+    /// **Returns** whether the call was successful
+    fn decide(
+        &mut self,
+        _assignment: &Assignment,
+        _fallback: SolverLiteral,
+        _decision: &mut SolverLiteral,
+    ) -> bool {
+        true
+    }
+}
+
+/// A propagator holding one state per solver thread.
+///
+/// In [`Propagator::init()`] a state is created with [`ThreadState::init()`] for each solver
+/// thread.
+/// The remaining callbacks are forwarded to the state of the calling thread,
+/// so that no two threads share mutable state.
+#[derive(Debug)]
+pub struct ThreadLocalPropagator<S: ThreadState> {
+    states: Vec<S>,
+}
+impl<S: ThreadState> Default for ThreadLocalPropagator<S> {
+    fn default() -> Self {
+        ThreadLocalPropagator { states: vec![] }
+    }
+}
+impl<S: ThreadState> ThreadLocalPropagator<S> {
+    /// Create a propagator without states, they are created on initialization.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Get the states of all solver threads indexed by thread id.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+    /// Get the state of the solver thread with the given id.
+    pub fn state(&self, thread_id: u32) -> Option<&S> {
+        self.states.get(thread_id as usize)
+    }
+    /// Get the state of the solver thread with the given id.
+    pub fn state_mut(&mut self, thread_id: u32) -> Option<&mut S> {
+        self.states.get_mut(thread_id as usize)
+    }
+}
+impl<S: ThreadState> Propagator for ThreadLocalPropagator<S> {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let threads = init.number_of_threads() as u32;
+        let mut states = Vec::with_capacity(threads as usize);
+        for thread_id in 0..threads {
+            match S::init(init, thread_id) {
+                Ok(state) => states.push(state),
+                Err(error) => {
+                    report_error(&error);
+                    return false;
+                }
+            }
+        }
+        self.states = states;
+        true
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        match self.state_mut(control.thread_id()) {
+            Some(state) => state.propagate(control, changes),
+            None => false,
+        }
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        if let Some(state) = self.state_mut(control.thread_id()) {
+            state.undo(control, changes)
+        }
+    }
+    fn check(&mut self, control: &mut PropagateControl) -> bool {
+        match self.state_mut(control.thread_id()) {
+            Some(state) => state.check(control),
+            None => false,
+        }
+    }
+    fn decide(
+        &mut self,
+        Id(thread_id): Id,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+        decision: &mut SolverLiteral,
+    ) -> bool {
+        match self.state_mut(thread_id) {
+            Some(state) => state.decide(assignment, fallback, decision),
+            None => false,
+        }
+    }
+}
+
+type UndoFn<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+/// A backtrackable value with a trail of changes keyed by decision level.
+///
+/// Changes are recorded together with the current decision level of the solver and rolled back
+/// with [`Trail::undo()`], which is meant to be called in [`Propagator::undo()`].
+/// Clingo calls [`Propagator::undo()`] only for decision levels on which a propagator has been
+/// called with changes, so changes should be recorded in [`Propagator::propagate()`].
+pub struct Trail<T> {
+    value: T,
+    changes: Vec<(u32, UndoFn<T>)>,
+}
+impl<T: fmt::Debug> fmt::Debug for Trail<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trail")
+            .field("value", &self.value)
+            .field(
+                "levels",
+                &self
+                    .changes
+                    .iter()
+                    .map(|(level, _)| level)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+impl<T: Default> Default for Trail<T> {
+    fn default() -> Self {
+        Trail::new(T::default())
+    }
+}
+impl<T> Trail<T> {
+    /// Create a trail with the given initial value.
+    pub fn new(value: T) -> Self {
+        Trail {
+            value,
+            changes: vec![],
+        }
+    }
+    /// Get the current value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+    /// Get the decision level of the most recent change or `None` if there are no changes.
+    pub fn level(&self) -> Option<u32> {
+        self.changes.last().map(|(level, _)| *level)
+    }
+    /// Modify the value and record how to undo the modification.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignment` - the assignment of the solver making the change
+    /// * `change` - the modification of the value
+    /// * `undo` - the function restoring the value from before the modification
+    ///
+    /// **Returns** the result of `change`
+    pub fn modify<R>(
+        &mut self,
+        assignment: &Assignment,
+        change: impl FnOnce(&mut T) -> R,
+        undo: impl FnOnce(&mut T) + Send + 'static,
+    ) -> R {
+        let result = change(&mut self.value);
+        self.changes
+            .push((assignment.decision_level(), Box::new(undo)));
+        result
+    }
+    /// Roll back all changes made on the current decision level or above.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - control object for the target solver
+    pub fn undo(&mut self, control: &PropagateControl) {
+        if let Ok(assignment) = control.assignment() {
+            self.backtrack(assignment.decision_level())
+        }
+    }
+    /// Roll back all changes made on the given decision level or above.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - the decision level to backtrack
+    pub fn backtrack(&mut self, level: u32) {
+        while let Some((last, _)) = self.changes.last() {
+            if *last < level {
+                break;
+            }
+            if let Some((_, undo)) = self.changes.pop() {
+                undo(&mut self.value);
+            }
+        }
+    }
+}
+impl<T: Clone + Send + 'static> Trail<T> {
+    /// Replace the value and record the previous value to restore it on backtracking.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignment` - the assignment of the solver making the change
+    /// * `value` - the new value
+    pub fn set(&mut self, assignment: &Assignment, value: T) {
+        let previous = std::mem::replace(&mut self.value, value);
+        self.changes.push((
+            assignment.decision_level(),
+            Box::new(move |value| *value = previous),
+        ));
+    }
+}
+
+/// Bidirectional mapping between symbols of atoms and solver literals.
+///
+/// Several atoms can be mapped to the same solver literal,
+/// for example, all facts are mapped to the literal that is always true.
+///
+/// See [`PropagateInit::map_symbols()`] and [`PropagateInit::watch_symbols()`].
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMap {
+    literals: HashMap<Symbol, SolverLiteral>,
+    symbols: HashMap<SolverLiteral, Vec<Symbol>>,
+}
+impl SymbolMap {
+    /// Create an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a mapping between a symbol and a solver literal.
+    ///
+    /// **Returns** the solver literal previously mapped to the symbol
+    pub fn insert(&mut self, symbol: Symbol, literal: SolverLiteral) -> Option<SolverLiteral> {
+        let previous = self.literals.insert(symbol, literal);
+        if let Some(previous) = previous {
+            if let Some(symbols) = self.symbols.get_mut(&previous) {
+                symbols.retain(|x| *x != symbol);
+                if symbols.is_empty() {
+                    self.symbols.remove(&previous);
+                }
+            }
+        }
+        self.symbols.entry(literal).or_default().push(symbol);
+        previous
+    }
+    /// Get the solver literal of the given symbol.
+    pub fn literal(&self, symbol: Symbol) -> Option<SolverLiteral> {
+        self.literals.get(&symbol).copied()
+    }
+    /// Get the symbols mapped to the given solver literal.
+    pub fn symbols(&self, literal: SolverLiteral) -> &[Symbol] {
+        self.symbols
+            .get(&literal)
+            .map_or(&[], |symbols| symbols.as_slice())
+    }
+    /// Get the distinct solver literals of the mapping.
+    pub fn literals(&self) -> impl Iterator<Item = SolverLiteral> + '_ {
+        self.symbols.keys().copied()
+    }
+    /// Iterate over all pairs of symbols and solver literals.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, SolverLiteral)> + '_ {
+        self.literals
+            .iter()
+            .map(|(symbol, literal)| (*symbol, *literal))
+    }
+    /// Get the number of mapped symbols.
+    pub fn len(&self) -> usize {
+        self.literals.len()
+    }
+    /// Check whether no symbol is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.literals.is_empty()
+    }
+}
+
+/// A clause together with the type determining its lifetime.
+#[derive(Debug, Clone)]
+pub struct Clause {
+    /// The literals of the clause
+    pub literals: Vec<SolverLiteral>,
+    /// The type of the clause
+    pub clause_type: ClauseType,
+}
+impl Clause {
+    /// Create a clause of the given type.
+    pub fn new(literals: Vec<SolverLiteral>, clause_type: ClauseType) -> Self {
+        Clause {
+            literals,
+            clause_type,
+        }
+    }
+    /// Create a clause subject to the solver's deletion policy.
+    pub fn learnt(literals: Vec<SolverLiteral>) -> Self {
+        Clause::new(literals, ClauseType::Learnt)
+    }
+    /// Create a clause that is not subject to the solver's deletion policy.
+    pub fn fixed(literals: Vec<SolverLiteral>) -> Self {
+        Clause::new(literals, ClauseType::Static)
+    }
+}
+
+/// The result of a propagation step of a [`ClauseGenerator`].
+#[derive(Debug, Clone)]
+pub enum Propagation {
+    /// Clauses to add, possibly none.
+    Clauses(Vec<Clause>),
+    /// A clause explaining a conflict, all its literals are false w.r.t. the current assignment.
+    Conflict(Clause),
+}
+impl Default for Propagation {
+    fn default() -> Self {
+        Propagation::Clauses(vec![])
+    }
+}
+
+/// A propagator that reports its consequences as clauses instead of adding them itself.
+///
+/// Use [`LazyClausePropagator`] to register a clause generator with a control object.
+pub trait ClauseGenerator {
+    /// Initialize the clause generator, see [`Propagator::init()`].
+    ///
+    /// **Returns** whether the call was successful
+    fn init(&mut self, _init: &mut PropagateInit) -> bool {
+        true
+    }
+    /// Generate clauses for the given change set, see [`Propagator::propagate()`].
+    fn propagate(
+        &mut self,
+        _control: &PropagateControl,
+        _changes: &[SolverLiteral],
+    ) -> Propagation {
+        Propagation::default()
+    }
+    /// Undo assignment dependent state, see [`Propagator::undo()`].
+    fn undo(&mut self, _control: &PropagateControl, _changes: &[SolverLiteral]) {}
+
+    /// Generate clauses for the current assignment, see [`Propagator::check()`].
+    fn check(&mut self, _control: &PropagateControl) -> Propagation {
+        Propagation::default()
+    }
+}
+
+/// A propagator adding the clauses of a [`ClauseGenerator`].
+///
+/// Clauses are added one after the other and propagated immediately.
+/// Adding clauses stops as soon as the solver reports a conflict.
+#[derive(Debug, Default)]
+pub struct LazyClausePropagator<G: ClauseGenerator> {
+    generator: G,
+}
+impl<G: ClauseGenerator> LazyClausePropagator<G> {
+    /// Create a propagator for the given clause generator.
+    pub fn new(generator: G) -> Self {
+        LazyClausePropagator { generator }
+    }
+    /// Get the clause generator.
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+    /// Get the clause generator.
+    pub fn generator_mut(&mut self) -> &mut G {
+        &mut self.generator
+    }
+    /// Add the clauses of a propagation step.
+    ///
+    /// **Returns** whether the call was successful
+    fn add(control: &mut PropagateControl, propagation: Propagation) -> bool {
+        let clauses = match propagation {
+            Propagation::Clauses(clauses) => clauses,
+            Propagation::Conflict(clause) => vec![clause],
+        };
+        for clause in clauses {
+            match control
+                .add_clause(&clause.literals, clause.clause_type)
+                .and_then(|added| Ok(added && control.propagate()?))
+            {
+                Ok(true) => {}
+                Ok(false) => return true,
+                Err(error) => {
+                    report_error(&error);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+impl<G: ClauseGenerator> Propagator for LazyClausePropagator<G> {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        self.generator.init(init)
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        let propagation = self.generator.propagate(control, changes);
+        Self::add(control, propagation)
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        self.generator.undo(control, changes)
+    }
+    fn check(&mut self, control: &mut PropagateControl) -> bool {
+        let propagation = self.generator.check(control);
+        Self::add(control, propagation)
+    }
+}
+
+/// A domain-specific decision heuristic.
+///
+/// Use [`GenericControl::add_heuristic()`](crate::GenericControl::add_heuristic) to register a
+/// heuristic as a propagator that only makes decisions.
+pub trait Heuristic {
+    /// Initialize the heuristic, see [`Propagator::init()`].
+    ///
+    /// **Returns** whether the call was successful
+    fn init(&mut self, _init: &mut PropagateInit) -> bool {
+        true
+    }
+    /// Observe the assignment of watched literals, see [`Propagator::propagate()`].
+    fn propagate(&mut self, _control: &PropagateControl, _changes: &[SolverLiteral]) {}
+
+    /// Observe the unassignment of watched literals, see [`Propagator::undo()`].
+    fn undo(&mut self, _control: &PropagateControl, _changes: &[SolverLiteral]) {}
+
+    /// Choose a free solver literal to be assigned true, see [`Propagator::decide()`].
+    ///
+    /// **Returns** the literal or `None` to leave the decision to the solver
+    fn decide(
+        &mut self,
+        thread_id: u32,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+    ) -> Option<SolverLiteral>;
+}
+
+/// A propagator making the decisions of a [`Heuristic`].
+#[derive(Debug, Default)]
+pub struct HeuristicPropagator<H: Heuristic> {
+    heuristic: H,
+}
+impl<H: Heuristic> HeuristicPropagator<H> {
+    /// Create a propagator for the given heuristic.
+    pub fn new(heuristic: H) -> Self {
+        HeuristicPropagator { heuristic }
+    }
+    /// Get the heuristic.
+    pub fn heuristic(&self) -> &H {
+        &self.heuristic
+    }
+}
+impl<H: Heuristic> Propagator for HeuristicPropagator<H> {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        self.heuristic.init(init)
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        self.heuristic.propagate(control, changes);
+        true
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        self.heuristic.undo(control, changes)
+    }
+    fn decide(
+        &mut self,
+        Id(thread_id): Id,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+        decision: &mut SolverLiteral,
+    ) -> bool {
+        if let Some(literal) = self.heuristic.decide(thread_id, assignment, fallback) {
+            *decision = literal;
+        }
+        true
+    }
+}
+
+/// Get the first free literal of the given literals.
+fn first_free(assignment: &Assignment, literals: &[SolverLiteral]) -> Option<SolverLiteral> {
+    literals
+        .iter()
+        .copied()
+        .find(|literal| matches!(assignment.truth_value(*literal), Ok(TruthValue::Free)))
+}
+
+/// A heuristic deciding atoms of the given signatures with a preferred sign.
+///
+/// Atoms are decided in the order in which their signatures were added,
+/// atoms of the same signature in the order of their symbols.
+#[derive(Debug, Clone, Default)]
+pub struct SignHeuristic {
+    signs: Vec<(Signature, bool)>,
+    literals: Vec<SolverLiteral>,
+}
+impl SignHeuristic {
+    /// Create a heuristic without preferences.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Prefer to assign atoms with the given signature the given truth value.
+    pub fn prefer(mut self, signature: Signature, sign: bool) -> Self {
+        self.signs.push((signature, sign));
+        self
+    }
+}
+impl Heuristic for SignHeuristic {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        self.literals.clear();
+        for (signature, sign) in &self.signs {
+            let map = match init.map_symbols(&[*signature]) {
+                Ok(map) => map,
+                Err(_) => return false,
+            };
+            let mut atoms: Vec<(Symbol, SolverLiteral)> = map.iter().collect();
+            atoms.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            self.literals.extend(atoms.into_iter().map(|(_, literal)| {
+                if *sign {
+                    literal
+                } else {
+                    literal.negate()
+                }
+            }));
+        }
+        true
+    }
+    fn decide(
+        &mut self,
+        _thread_id: u32,
+        assignment: &Assignment,
+        _fallback: SolverLiteral,
+    ) -> Option<SolverLiteral> {
+        first_free(assignment, &self.literals)
+    }
+}
+
+/// A heuristic deciding atoms in the order of their scores.
+///
+/// Atoms with higher scores are made true first.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityHeuristic {
+    scores: HashMap<Symbol, i64>,
+    literals: Vec<SolverLiteral>,
+}
+impl PriorityHeuristic {
+    /// Create a heuristic for the given symbol scores.
+    pub fn new(scores: impl IntoIterator<Item = (Symbol, i64)>) -> Self {
+        PriorityHeuristic {
+            scores: scores.into_iter().collect(),
+            literals: vec![],
+        }
+    }
+}
+impl Heuristic for PriorityHeuristic {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let mut signatures = vec![];
+        for symbol in self.scores.keys() {
+            match symbol.signature() {
+                Ok(signature) if !signatures.contains(&signature) => signatures.push(signature),
+                Ok(_) => {}
+                Err(_) => return false,
+            }
+        }
+        let map = match init.map_symbols(&signatures) {
+            Ok(map) => map,
+            Err(_) => return false,
+        };
+        let mut scored: Vec<(i64, Symbol, SolverLiteral)> = map
+            .iter()
+            .filter_map(|(symbol, literal)| {
+                self.scores
+                    .get(&symbol)
+                    .map(|score| (*score, symbol, literal))
+            })
+            .collect();
+        scored.sort_by(|(a, x, _), (b, y, _)| {
+            b.cmp(a).then(x.partial_cmp(y).unwrap_or(Ordering::Equal))
+        });
+        self.literals = scored.into_iter().map(|(_, _, literal)| literal).collect();
+        true
+    }
+    fn decide(
+        &mut self,
+        _thread_id: u32,
+        assignment: &Assignment,
+        _fallback: SolverLiteral,
+    ) -> Option<SolverLiteral> {
+        first_free(assignment, &self.literals)
+    }
+}
+
+/// An entry of the activity heap, see [`AssignmentActivityHeuristic`].
+///
+/// Entries are ordered by activity and variables with the same activity in ascending order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ActivityEntry {
+    activity: f64,
+    variable: SolverLiteral,
+}
+impl Eq for ActivityEntry {}
+impl PartialOrd for ActivityEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ActivityEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.activity
+            .total_cmp(&other.activity)
+            .then_with(|| other.variable.0.cmp(&self.variable.0))
+    }
+}
+
+/// A heuristic deciding the atoms assigned most often recently first.
+///
+/// The activity of an atom is bumped whenever it is assigned, by propagation or by a decision,
+/// and decays over time.
+/// Unlike VSIDS, activities are not bumped for atoms involved in conflicts, because heuristics
+/// are not notified about conflicts.
+/// Decided atoms get the truth value they were assigned last.
+///
+/// Each solver thread keeps the free atoms in a priority queue ordered by activity, where
+/// outdated entries are skipped when deciding and unassigned atoms are added back on undo.
+#[derive(Debug, Clone)]
+pub struct AssignmentActivityHeuristic {
+    signatures: Vec<Signature>,
+    decay: f64,
+    increment: f64,
+    activity: HashMap<SolverLiteral, f64>,
+    phase: HashMap<SolverLiteral, bool>,
+    variables: Vec<SolverLiteral>,
+    heaps: Vec<BinaryHeap<ActivityEntry>>,
+}
+impl AssignmentActivityHeuristic {
+    /// Create a heuristic for the atoms with the given signatures.
+    pub fn new(signatures: &[Signature]) -> Self {
+        AssignmentActivityHeuristic {
+            signatures: signatures.to_vec(),
+            decay: 0.95,
+            increment: 1.0,
+            activity: HashMap::new(),
+            phase: HashMap::new(),
+            variables: vec![],
+            heaps: vec![],
+        }
+    }
+    /// Set the factor by which activities decay, the default is `0.95`.
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        self.decay = decay;
+        self
+    }
+    /// Get the activity of the atom with the given solver literal.
+    pub fn activity(&self, literal: SolverLiteral) -> f64 {
+        let variable = SolverLiteral(literal.0.abs());
+        self.activity.get(&variable).copied().unwrap_or_default()
+    }
+    fn entry(&self, variable: SolverLiteral) -> ActivityEntry {
+        ActivityEntry {
+            activity: self.activity(variable),
+            variable,
+        }
+    }
+    /// Rebuild the heaps from the current activities dropping outdated entries.
+    fn rebuild(&mut self) {
+        let heap: BinaryHeap<ActivityEntry> = self
+            .variables
+            .iter()
+            .map(|variable| self.entry(*variable))
+            .collect();
+        for entries in &mut self.heaps {
+            entries.clone_from(&heap);
+        }
+    }
+}
+impl Heuristic for AssignmentActivityHeuristic {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let map = match init.map_symbols(&self.signatures) {
+            Ok(map) => map,
+            Err(_) => return false,
+        };
+        let mut variables = HashSet::new();
+        for literal in map.literals() {
+            let variable = SolverLiteral(literal.0.abs());
+            let fixed = match init.assignment() {
+                Ok(assignment) => assignment.is_fixed(variable).unwrap_or(true),
+                Err(_) => return false,
+            };
+            if !fixed {
+                variables.insert(variable);
+            }
+        }
+        let mut variables: Vec<SolverLiteral> = variables.into_iter().collect();
+        variables.sort_by_key(|variable| variable.0);
+        for variable in &variables {
+            if init.add_watch(*variable).is_err() || init.add_watch(variable.negate()).is_err() {
+                return false;
+            }
+            self.activity.entry(*variable).or_default();
+        }
+        self.variables = variables;
+        self.heaps = vec![BinaryHeap::new(); init.number_of_threads()];
+        self.rebuild();
+        true
+    }
+    fn propagate(&mut self, _control: &PropagateControl, changes: &[SolverLiteral]) {
+        for literal in changes {
+            let variable = SolverLiteral(literal.0.abs());
+            *self.activity.entry(variable).or_default() += self.increment;
+            self.phase.insert(variable, literal.0 > 0);
+            let entry = self.entry(variable);
+            for heap in &mut self.heaps {
+                heap.push(entry);
+            }
+        }
+        self.increment /= self.decay;
+        if self.increment > 1e100 {
+            for activity in self.activity.values_mut() {
+                *activity *= 1e-100;
+            }
+            self.increment *= 1e-100;
+            self.rebuild();
+        } else if self
+            .heaps
+            .iter()
+            .any(|heap| heap.len() > 4 * self.variables.len() + 64)
+        {
+            self.rebuild();
+        }
+    }
+    fn undo(&mut self, control: &PropagateControl, changes: &[SolverLiteral]) {
+        let thread = control.thread_id() as usize;
+        for literal in changes {
+            let entry = self.entry(SolverLiteral(literal.0.abs()));
+            if let Some(heap) = self.heaps.get_mut(thread) {
+                heap.push(entry);
+            }
+        }
+    }
+    fn decide(
+        &mut self,
+        thread_id: u32,
+        assignment: &Assignment,
+        _fallback: SolverLiteral,
+    ) -> Option<SolverLiteral> {
+        let heap = self.heaps.get_mut(thread_id as usize)?;
+        while let Some(entry) = heap.peek() {
+            let variable = entry.variable;
+            let current = self.activity.get(&variable).copied().unwrap_or_default();
+            if entry.activity == current
+                && matches!(assignment.truth_value(variable), Ok(TruthValue::Free))
+            {
+                return Some(match self.phase.get(&variable) {
+                    Some(false) => variable.negate(),
+                    _ => variable,
+                });
+            }
+            // outdated entries and assigned atoms are added back on bumps and undo
+            heap.pop();
+        }
+        None
+    }
+}
@@ -3,8 +3,24 @@
 #![allow(non_snake_case)]
 
 use super::{GenericControl, Id, Model, Options, Statistics, Symbol};
-use crate::{ast, ControlCtx};
+use crate::ast::{parse_string_with_statement_handler, ProgramBuilder, StatementHandler};
+use crate::{
+    ast, ClingoError, ControlCtx, GenericSolveHandle, Part, Propagator, PropagatorCheckMode,
+    SolveEvent, SolveEventHandler, SolveMode, SolverLiteral,
+};
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Mutex;
+
+/// A difference logic theory for `&diff` constraints.
+pub mod difference_logic;
+/// Theories loaded from plugin libraries exporting the clingo theory ABI.
+///
+/// **Note:** This module is experimental and requires the `dynamic` feature.
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+/// A linear integer constraint theory for `&sum` constraints.
+pub mod linear;
 
 pub trait Theory<'a> {
     /// registers the theory with the control
@@ -45,16 +61,240 @@ pub trait Theory<'a> {
 }
 #[derive(Copy, Clone, Debug)]
 pub enum TheoryValue {
-    IntNumber(u64),
+    IntNumber(i64),
     DoubleNumber(f64),
     Symbol(Symbol),
 }
 impl fmt::Display for TheoryValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            TheoryValue::IntNumber(int) => u64::fmt(int, f),
+            TheoryValue::IntNumber(int) => i64::fmt(int, f),
             TheoryValue::DoubleNumber(double) => f64::fmt(double, f),
             TheoryValue::Symbol(sym) => Symbol::fmt(sym, f),
         }
     }
 }
+
+/// Drives a [`Theory`] through the life cycle of a control object.
+///
+/// The runner registers the theory, rewrites programs with [`Theory::rewrite_statement()`],
+/// prepares the theory after grounding, and passes models and statistics to the theory while
+/// solving.
+#[derive(Debug, Clone, Default)]
+pub struct TheoryRunner<T> {
+    theory: T,
+}
+impl<'a, T: Theory<'a>> TheoryRunner<T> {
+    /// Create a runner for the given theory.
+    pub fn new(theory: T) -> Self {
+        TheoryRunner { theory }
+    }
+    /// Get the theory.
+    pub fn theory(&self) -> &T {
+        &self.theory
+    }
+    /// Get the theory.
+    pub fn theory_mut(&mut self) -> &mut T {
+        &mut self.theory
+    }
+    /// Get the theory, consuming the runner.
+    pub fn into_theory(self) -> T {
+        self.theory
+    }
+    /// Register the theory with the control object, see [`Theory::register()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::ExternalError`] if the theory could not be registered
+    pub fn register<C: ControlCtx>(
+        &mut self,
+        ctl: &mut GenericControl<C>,
+    ) -> Result<(), ClingoError> {
+        if !self.theory.register(ctl) {
+            return Err(ClingoError::new_external("Could not register theory"));
+        }
+        Ok(())
+    }
+    /// Parse the given program and add its statements rewritten by the theory to the control
+    /// object, see [`Theory::rewrite_statement()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] if the program could not be parsed or a statement could
+    ///   not be rewritten
+    pub fn add<C: ControlCtx>(
+        &mut self,
+        ctl: &mut GenericControl<C>,
+        program: &str,
+    ) -> Result<(), ClingoError> {
+        let mut handler = RewriteHandler {
+            theory: &mut self.theory,
+            builder: ProgramBuilder::from(ctl)?,
+        };
+        let result = parse_string_with_statement_handler(program, &mut handler);
+        let end = handler.builder.end();
+        result.and(end)
+    }
+    /// Ground the given program parts and prepare the theory, see [`Theory::prepare()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] if grounding fails
+    /// - [`ClingoError::ExternalError`] if the theory could not be prepared
+    pub fn ground<C: ControlCtx>(
+        &mut self,
+        ctl: &mut GenericControl<C>,
+        parts: &[Part],
+    ) -> Result<(), ClingoError> {
+        ctl.ground(parts)?;
+        if !self.theory.prepare(ctl) {
+            return Err(ClingoError::new_external("Could not prepare theory"));
+        }
+        Ok(())
+    }
+    /// Solve with the given mode and assumptions.
+    ///
+    /// Models and statistics are passed to [`Theory::on_model()`] and
+    /// [`Theory::on_statistics()`] before the given event handler sees them.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::InternalError`] if solving could not be started
+    pub fn solve<C: ControlCtx, H: SolveEventHandler>(
+        &mut self,
+        ctl: GenericControl<C>,
+        mode: SolveMode,
+        assumptions: &[SolverLiteral],
+        handler: H,
+    ) -> Result<GenericSolveHandle<C, TheoryEventHandler<'_, T, H>>, ClingoError> {
+        let handler = TheoryEventHandler {
+            theory: &mut self.theory,
+            handler,
+        };
+        ctl.solve_with_event_handler(mode, assumptions, handler)
+    }
+}
+
+struct RewriteHandler<'t, 'b, T> {
+    theory: &'t mut T,
+    builder: ProgramBuilder<'b>,
+}
+impl<'a, T: Theory<'a>> StatementHandler for RewriteHandler<'_, '_, T> {
+    fn on_statement(&mut self, stm: &ast::Statement) -> bool {
+        self.theory.rewrite_statement(stm, &mut self.builder)
+    }
+}
+
+/// Solve event handler passing models and statistics to a theory, see [`TheoryRunner::solve()`].
+#[derive(Debug)]
+pub struct TheoryEventHandler<'t, T, H> {
+    theory: &'t mut T,
+    handler: H,
+}
+impl<'a, T: Theory<'a>, H: SolveEventHandler> SolveEventHandler for TheoryEventHandler<'_, T, H> {
+    fn on_solve_event(&mut self, event: SolveEvent, goon: &mut bool) -> bool {
+        match event {
+            SolveEvent::Model(model) => {
+                self.theory.on_model(model)
+                    && self.handler.on_solve_event(SolveEvent::Model(model), goon)
+            }
+            SolveEvent::Statistics { step, akku } => {
+                self.theory.on_statistics(step, akku)
+                    && self
+                        .handler
+                        .on_solve_event(SolveEvent::Statistics { step, akku }, goon)
+            }
+            event => self.handler.on_solve_event(event, goon),
+        }
+    }
+}
+
+/// The integer variables of a theory state indexed by their symbols.
+pub(crate) trait IntegerVariables {
+    /// The symbols of the variables.
+    fn symbols(&self) -> &[Symbol];
+    /// The indices of the variables.
+    fn index(&self) -> &HashMap<Symbol, usize>;
+}
+
+/// Add the theory definition to the `base` program and the propagator to the control object,
+/// see [`Theory::register()`].
+pub(crate) fn register_propagator<C, P>(
+    ctl: &mut GenericControl<C>,
+    theory: &str,
+    propagator: P,
+    check_mode: PropagatorCheckMode,
+) -> bool
+where
+    C: ControlCtx,
+    P: Propagator + Send + 'static,
+{
+    ctl.add("base", &[], theory).is_ok()
+        && ctl.add_propagator(propagator, check_mode, false).is_ok()
+}
+
+/// Extend the model with atoms `name(variable,value)` for the values of the model's solver
+/// thread, see [`Theory::on_model()`].
+pub(crate) fn extend_model(
+    model: &mut Model,
+    name: &str,
+    values: impl FnOnce(u32) -> Vec<(Symbol, i64)>,
+) -> bool {
+    let thread_id = match model.thread_id() {
+        Ok(thread_id) => thread_id.get_integer(),
+        Err(_) => return false,
+    };
+    let mut symbols = vec![];
+    for (symbol, value) in values(thread_id) {
+        let value = match i32::try_from(value) {
+            Ok(value) => Symbol::create_number(value),
+            Err(_) => return false,
+        };
+        match Symbol::create_function(name, &[symbol, value], true) {
+            Ok(symbol) => symbols.push(symbol),
+            Err(_) => return false,
+        }
+    }
+    model.extend(&symbols)
+}
+
+/// Look up the index of a variable, see [`Theory::lookup_symbol()`].
+pub(crate) fn lookup_symbol<S: IntegerVariables>(
+    state: &Mutex<S>,
+    symbol: Symbol,
+    index: &mut usize,
+) -> bool {
+    match state.lock() {
+        Ok(state) => match state.index().get(&symbol) {
+            Some(found) => {
+                *index = *found;
+                true
+            }
+            None => false,
+        },
+        Err(_) => false,
+    }
+}
+
+/// Get the symbol of the variable at the given index, see [`Theory::get_symbol()`].
+pub(crate) fn get_symbol<S: IntegerVariables>(state: &Mutex<S>, index: usize) -> Symbol {
+    match state.lock() {
+        Ok(state) => state
+            .symbols()
+            .get(index)
+            .copied()
+            .unwrap_or_else(Symbol::create_infimum),
+        Err(_) => Symbol::create_infimum(),
+    }
+}
+
+/// Turn integer values into an assignment, see [`Theory::assignment()`].
+pub(crate) fn assignment<'a>(
+    values: Vec<(Symbol, i64)>,
+) -> Box<dyn Iterator<Item = (Symbol, TheoryValue)> + 'a> {
+    Box::new(
+        values
+            .into_iter()
+            .map(|(symbol, value)| (symbol, TheoryValue::IntNumber(value))),
+    )
+}
@@ -0,0 +1,333 @@
+use super::{IntegerVariables, Theory, TheoryValue};
+use crate::{
+    ast, report_error, theory, ClauseType, ClingoError, ControlCtx, GenericControl, Id, Model,
+    Options, PropagateControl, PropagateInit, Propagator, PropagatorCheckMode, SolverLiteral,
+    Statistics, Symbol, TheoryAtomView, TheoryTermView,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Theory definition of difference constraints `&diff{ u - v } <= k`.
+///
+/// A term `u` without subtrahend is read as `u - 0`.
+pub const THEORY: &str = "#theory dl {
+    constant  { - : 0, unary };
+    diff_term { - : 0, binary, left };
+    &diff/0 : diff_term, {<=}, constant, any
+}.";
+
+/// Edge `from -> to` with `weight` requiring `x(to) <= x(from) + weight` if `literal` is true.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: usize,
+    to: usize,
+    weight: i64,
+    literal: SolverLiteral,
+}
+
+/// Assignment of a solver thread.
+#[derive(Debug, Clone, Default)]
+struct Graph {
+    potential: Vec<i64>,
+    outgoing: Vec<Vec<usize>>,
+}
+impl Graph {
+    fn new(nodes: usize) -> Self {
+        Graph {
+            potential: vec![0; nodes],
+            outgoing: vec![vec![]; nodes],
+        }
+    }
+    /// Add an edge and repair the potentials.
+    ///
+    /// **Returns** the edges of a negative cycle if the edge cannot be added
+    fn add_edge(&mut self, edges: &[Edge], edge: usize) -> Result<(), Vec<usize>> {
+        let Edge {
+            from, to, weight, ..
+        } = edges[edge];
+        if self.potential[from] + weight < self.potential[to] {
+            let mut changed = vec![(to, self.potential[to])];
+            let mut predecessor = HashMap::new();
+            self.potential[to] = self.potential[from] + weight;
+            predecessor.insert(to, edge);
+            let mut queue = VecDeque::from([to]);
+            while let Some(node) = queue.pop_front() {
+                for &next in &self.outgoing[node] {
+                    let Edge { to: m, weight, .. } = edges[next];
+                    if self.potential[node] + weight >= self.potential[m] {
+                        continue;
+                    }
+                    if m == from {
+                        let mut cycle = vec![next];
+                        let mut current = node;
+                        while current != to && cycle.len() <= edges.len() {
+                            let previous = predecessor[&current];
+                            cycle.push(previous);
+                            current = edges[previous].from;
+                        }
+                        cycle.push(edge);
+                        for (node, value) in changed.into_iter().rev() {
+                            self.potential[node] = value;
+                        }
+                        return Err(cycle);
+                    }
+                    changed.push((m, self.potential[m]));
+                    self.potential[m] = self.potential[node] + weight;
+                    predecessor.insert(m, next);
+                    queue.push_back(m);
+                }
+            }
+        }
+        self.outgoing[from].push(edge);
+        Ok(())
+    }
+    fn remove_edge(&mut self, edges: &[Edge], edge: usize) {
+        self.outgoing[edges[edge].from].retain(|x| *x != edge);
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    nodes: Vec<Symbol>,
+    index: HashMap<Symbol, usize>,
+    edges: Vec<Edge>,
+    watches: HashMap<SolverLiteral, Vec<usize>>,
+    graphs: Vec<Graph>,
+}
+impl IntegerVariables for State {
+    fn symbols(&self) -> &[Symbol] {
+        &self.nodes
+    }
+    fn index(&self) -> &HashMap<Symbol, usize> {
+        &self.index
+    }
+}
+impl State {
+    fn node(&mut self, symbol: Symbol) -> usize {
+        if let Some(index) = self.index.get(&symbol) {
+            return *index;
+        }
+        self.nodes.push(symbol);
+        self.index.insert(symbol, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+    /// Add the edges of a `&diff` atom.
+    fn add_atom(&mut self, init: &PropagateInit, atom: &TheoryAtomView) -> Result<(), ClingoError> {
+        let error = || ClingoError::new_external("Invalid difference constraint");
+        let term = match atom.elements.as_slice() {
+            [element] if element.condition.is_empty() => match element.tuple.as_slice() {
+                [term] => term,
+                _ => return Err(error()),
+            },
+            _ => return Err(error()),
+        };
+        let (u, v) = match term {
+            TheoryTermView::Function(name, arguments) if name == "-" && arguments.len() == 2 => {
+                (arguments[0].to_symbol()?, arguments[1].to_symbol()?)
+            }
+            term => (term.to_symbol()?, Symbol::create_number(0)),
+        };
+        let bound = match &atom.guard {
+            Some((operator, bound)) if operator == "<=" => bound.to_symbol()?.number()? as i64,
+            _ => return Err(error()),
+        };
+        let (u, v) = (self.node(u), self.node(v));
+        let literal = init.solver_literal(atom.literal)?;
+        // u - v <= bound
+        self.edges.push(Edge {
+            from: v,
+            to: u,
+            weight: bound,
+            literal,
+        });
+        // v - u <= -bound - 1
+        self.edges.push(Edge {
+            from: u,
+            to: v,
+            weight: -bound - 1,
+            literal: literal.negate(),
+        });
+        Ok(())
+    }
+    fn values(&self, thread_id: usize) -> Vec<(Symbol, i64)> {
+        let graph = match self.graphs.get(thread_id) {
+            Some(graph) => graph,
+            None => return vec![],
+        };
+        let zero = Symbol::create_number(0);
+        let offset = self
+            .index
+            .get(&zero)
+            .map_or(0, |index| graph.potential[*index]);
+        self.nodes
+            .iter()
+            .zip(&graph.potential)
+            .filter(|(symbol, _)| **symbol != zero)
+            .map(|(symbol, value)| (*symbol, value - offset))
+            .collect()
+    }
+}
+
+/// Propagator checking the difference constraints.
+struct DifferenceLogicPropagator {
+    state: Arc<Mutex<State>>,
+}
+impl DifferenceLogicPropagator {
+    fn init_state(state: &mut State, init: &mut PropagateInit) -> Result<(), ClingoError> {
+        *state = State::default();
+        for atom in init.theory_atoms()?.views()? {
+            if atom.name() == "diff" {
+                state.add_atom(init, &atom)?;
+            }
+        }
+        state.graphs = vec![Graph::new(state.nodes.len()); init.number_of_threads()];
+        for edge in 0..state.edges.len() {
+            let literal = state.edges[edge].literal;
+            if init.assignment()?.is_true(literal)? {
+                for thread in 0..state.graphs.len() {
+                    if let Err(cycle) = state.graphs[thread].add_edge(&state.edges, edge) {
+                        let clause: Vec<SolverLiteral> = cycle
+                            .iter()
+                            .map(|edge| state.edges[*edge].literal.negate())
+                            .collect();
+                        // a conflict makes the problem unsatisfiable
+                        init.add_clause(&clause)?;
+                        return Ok(());
+                    }
+                }
+            } else if !init.assignment()?.is_false(literal)? {
+                state.watches.entry(literal).or_default().push(edge);
+            }
+        }
+        let literals: Vec<SolverLiteral> = state.watches.keys().copied().collect();
+        for literal in literals {
+            init.add_watch(literal)?;
+        }
+        Ok(())
+    }
+}
+impl Propagator for DifferenceLogicPropagator {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        match Self::init_state(&mut state, init) {
+            Ok(()) => true,
+            Err(error) => {
+                report_error(&error);
+                false
+            }
+        }
+    }
+    fn propagate(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        let state = &mut *state;
+        let thread = control.thread_id() as usize;
+        for literal in changes {
+            for &edge in state.watches.get(literal).into_iter().flatten() {
+                if let Err(cycle) = state.graphs[thread].add_edge(&state.edges, edge) {
+                    let clause: Vec<SolverLiteral> = cycle
+                        .iter()
+                        .map(|edge| state.edges[*edge].literal.negate())
+                        .collect();
+                    return match control.add_clause(&clause, ClauseType::Learnt) {
+                        Ok(true) => control.propagate().is_ok(),
+                        Ok(false) => true,
+                        Err(_) => false,
+                    };
+                }
+            }
+        }
+        true
+    }
+    fn undo(&mut self, control: &mut PropagateControl, changes: &[SolverLiteral]) {
+        if let Ok(mut state) = self.state.lock() {
+            let state = &mut *state;
+            let thread = control.thread_id() as usize;
+            for literal in changes {
+                for &edge in state.watches.get(literal).into_iter().flatten() {
+                    state.graphs[thread].remove_edge(&state.edges, edge);
+                }
+            }
+        }
+    }
+}
+
+/// A difference logic theory for constraints of form `&diff{ u - v } <= k`.
+///
+/// The theory adds its definition [`THEORY`] to the `base` program and a propagator to the
+/// control object on registration.
+/// A constraint holds if and only if its atom is true, so `u - v > k` holds for false atoms.
+/// Models are extended with atoms `dl(u,value)` holding the integer values of the variables.
+#[derive(Debug, Clone, Default)]
+pub struct DifferenceLogic {
+    state: Arc<Mutex<State>>,
+}
+impl DifferenceLogic {
+    /// Create a difference logic theory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Get the values of the variables in the current assignment of the given solver thread.
+    pub fn values(&self, thread_id: u32) -> Vec<(Symbol, i64)> {
+        match self.state.lock() {
+            Ok(state) => state.values(thread_id as usize),
+            Err(_) => vec![],
+        }
+    }
+}
+impl<'a> Theory<'a> for DifferenceLogic {
+    fn register<C>(&mut self, ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        let propagator = DifferenceLogicPropagator {
+            state: self.state.clone(),
+        };
+        theory::register_propagator(ctl, THEORY, propagator, PropagatorCheckMode::None)
+    }
+    fn rewrite_statement(
+        &mut self,
+        stm: &ast::Statement,
+        builder: &mut ast::ProgramBuilder,
+    ) -> bool {
+        builder.add(stm).is_ok()
+    }
+    fn prepare<C>(&mut self, _ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        true
+    }
+    fn register_options(&mut self, _options: &mut Options) -> bool {
+        true
+    }
+    fn validate_options(&mut self) -> bool {
+        true
+    }
+    fn on_model(&mut self, model: &mut Model) -> bool {
+        theory::extend_model(model, "dl", |thread_id| self.values(thread_id))
+    }
+    fn on_statistics(&mut self, _step: &mut Statistics, _akku: &mut Statistics) -> bool {
+        true
+    }
+    fn lookup_symbol(&mut self, symbol: Symbol, index: &mut usize) -> bool {
+        theory::lookup_symbol(&self.state, symbol, index)
+    }
+    fn get_symbol(&mut self, index: usize) -> Symbol {
+        theory::get_symbol(&self.state, index)
+    }
+    fn assignment(
+        &'a self,
+        Id(thread_id): Id,
+    ) -> Box<dyn Iterator<Item = (Symbol, TheoryValue)> + 'a> {
+        theory::assignment(self.values(thread_id))
+    }
+    fn configure(&mut self, _key: &str, _value: &str) -> bool {
+        false
+    }
+}
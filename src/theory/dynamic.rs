@@ -0,0 +1,276 @@
+use super::{Theory, TheoryValue};
+use crate::{ast, ClingoError, ControlCtx, GenericControl, Id, Model, Options, Statistics, Symbol};
+use clingo_sys::*;
+use libloading::Library;
+use std::ffi::{CString, OsStr};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::NonNull;
+
+type CreateFn = unsafe extern "C" fn(theory: *mut *mut c_void) -> bool;
+type DestroyFn = unsafe extern "C" fn(theory: *mut c_void) -> bool;
+type ControlFn = unsafe extern "C" fn(theory: *mut c_void, control: *mut clingo_control_t) -> bool;
+type AstCallback = unsafe extern "C" fn(ast: *mut clingo_ast_t, data: *mut c_void) -> bool;
+type RewriteAstFn = unsafe extern "C" fn(
+    theory: *mut c_void,
+    ast: *mut clingo_ast_t,
+    add: AstCallback,
+    data: *mut c_void,
+) -> bool;
+type RegisterOptionsFn =
+    unsafe extern "C" fn(theory: *mut c_void, options: *mut clingo_options_t) -> bool;
+type ValidateOptionsFn = unsafe extern "C" fn(theory: *mut c_void) -> bool;
+type OnModelFn = unsafe extern "C" fn(theory: *mut c_void, model: *mut clingo_model_t) -> bool;
+type OnStatisticsFn = unsafe extern "C" fn(
+    theory: *mut c_void,
+    step: *mut clingo_statistics_t,
+    akku: *mut clingo_statistics_t,
+) -> bool;
+type LookupSymbolFn =
+    unsafe extern "C" fn(theory: *mut c_void, symbol: clingo_symbol_t, index: *mut usize) -> bool;
+type GetSymbolFn = unsafe extern "C" fn(theory: *mut c_void, index: usize) -> clingo_symbol_t;
+type AssignmentBeginFn =
+    unsafe extern "C" fn(theory: *mut c_void, thread_id: u32, index: *mut usize);
+type AssignmentNextFn =
+    unsafe extern "C" fn(theory: *mut c_void, thread_id: u32, index: *mut usize) -> bool;
+type AssignmentHasValueFn =
+    unsafe extern "C" fn(theory: *mut c_void, thread_id: u32, index: usize) -> bool;
+type AssignmentGetValueFn =
+    unsafe extern "C" fn(theory: *mut c_void, thread_id: u32, index: usize, value: *mut Value);
+type ConfigureFn =
+    unsafe extern "C" fn(theory: *mut c_void, key: *const c_char, value: *const c_char) -> bool;
+
+/// Value type of theory plugins, e.g., `clingodl_value_t`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct Value {
+    value_type: c_int,
+    value: ValueUnion,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+union ValueUnion {
+    int_number: c_int,
+    double_number: f64,
+    symbol: clingo_symbol_t,
+}
+const VALUE_TYPE_INT: c_int = 0;
+const VALUE_TYPE_DOUBLE: c_int = 1;
+
+/// The functions of the theory ABI.
+struct Functions {
+    create: CreateFn,
+    destroy: DestroyFn,
+    register: ControlFn,
+    rewrite_ast: RewriteAstFn,
+    prepare: ControlFn,
+    register_options: RegisterOptionsFn,
+    validate_options: ValidateOptionsFn,
+    on_model: OnModelFn,
+    on_statistics: OnStatisticsFn,
+    lookup_symbol: LookupSymbolFn,
+    get_symbol: GetSymbolFn,
+    assignment_begin: AssignmentBeginFn,
+    assignment_next: AssignmentNextFn,
+    assignment_has_value: AssignmentHasValueFn,
+    assignment_get_value: AssignmentGetValueFn,
+    configure: ConfigureFn,
+}
+impl Functions {
+    /// Look up the functions with the given prefix in the library.
+    ///
+    /// # Safety
+    ///
+    /// The library has to export the functions with the signatures of the theory ABI.
+    unsafe fn load(library: &Library, prefix: &str) -> Result<Functions, ClingoError> {
+        unsafe fn get<T: Copy>(
+            library: &Library,
+            prefix: &str,
+            name: &str,
+        ) -> Result<T, ClingoError> {
+            let symbol = library.get::<T>(format!("{prefix}_{name}").as_bytes())?;
+            Ok(*symbol)
+        }
+        Ok(Functions {
+            create: get(library, prefix, "create")?,
+            destroy: get(library, prefix, "destroy")?,
+            register: get(library, prefix, "register")?,
+            rewrite_ast: get(library, prefix, "rewrite_ast")?,
+            prepare: get(library, prefix, "prepare")?,
+            register_options: get(library, prefix, "register_options")?,
+            validate_options: get(library, prefix, "validate_options")?,
+            on_model: get(library, prefix, "on_model")?,
+            on_statistics: get(library, prefix, "on_statistics")?,
+            lookup_symbol: get(library, prefix, "lookup_symbol")?,
+            get_symbol: get(library, prefix, "get_symbol")?,
+            assignment_begin: get(library, prefix, "assignment_begin")?,
+            assignment_next: get(library, prefix, "assignment_next")?,
+            assignment_has_value: get(library, prefix, "assignment_has_value")?,
+            assignment_get_value: get(library, prefix, "assignment_get_value")?,
+            configure: get(library, prefix, "configure")?,
+        })
+    }
+}
+
+unsafe extern "C" fn unsafe_add_ast(ast: *mut clingo_ast_t, data: *mut c_void) -> bool {
+    clingo_program_builder_add(data as *mut clingo_program_builder_t, ast)
+}
+
+/// A theory implemented by a plugin library exporting the clingo theory ABI.
+///
+/// Theories like clingo-dl or clingo-lpx export functions named after a prefix, e.g.,
+/// `clingodl_create()`, `clingodl_register()` and `clingodl_on_model()`.
+/// The plugin is created when loading the library and destroyed when the theory is dropped.
+///
+/// **Note:** Integer values of the assignment are read as C `int`.
+pub struct DynamicTheory {
+    theory: NonNull<c_void>,
+    functions: Functions,
+    // dropped after the theory has been destroyed
+    _library: Library,
+}
+impl std::fmt::Debug for DynamicTheory {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("DynamicTheory")
+            .field("theory", &self.theory)
+            .finish_non_exhaustive()
+    }
+}
+impl DynamicTheory {
+    /// Load a theory plugin from a shared library and create the theory.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the shared library, e.g., `libclingo-dl.so`
+    /// * `prefix` - the prefix of the exported functions, e.g., `clingodl`
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization routines and the library has to export the
+    /// functions of the theory ABI with the given prefix with matching signatures.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClingoError::LibraryError`] if the library could not be loaded or lacks a function
+    /// - [`ClingoError::InternalError`] if the theory could not be created
+    pub unsafe fn load(
+        path: impl AsRef<OsStr>,
+        prefix: &str,
+    ) -> Result<DynamicTheory, ClingoError> {
+        let library = Library::new(path)?;
+        let functions = Functions::load(&library, prefix)?;
+        let mut theory = std::ptr::null_mut();
+        if !(functions.create)(&mut theory) {
+            return Err(ClingoError::new_internal("Call to theory create() failed"));
+        }
+        match NonNull::new(theory) {
+            Some(theory) => Ok(DynamicTheory {
+                theory,
+                functions,
+                _library: library,
+            }),
+            None => Err(ClingoError::FFIError {
+                msg: "Tried creating NonNull from a null pointer.",
+            }),
+        }
+    }
+    fn value(&self, thread_id: u32, index: usize) -> TheoryValue {
+        let mut value = Value {
+            value_type: VALUE_TYPE_INT,
+            value: ValueUnion { int_number: 0 },
+        };
+        unsafe {
+            (self.functions.assignment_get_value)(
+                self.theory.as_ptr(),
+                thread_id,
+                index,
+                &mut value,
+            );
+            match value.value_type {
+                VALUE_TYPE_INT => TheoryValue::IntNumber(value.value.int_number.into()),
+                VALUE_TYPE_DOUBLE => TheoryValue::DoubleNumber(value.value.double_number),
+                _ => TheoryValue::Symbol(Symbol(value.value.symbol)),
+            }
+        }
+    }
+}
+impl Drop for DynamicTheory {
+    fn drop(&mut self) {
+        unsafe { (self.functions.destroy)(self.theory.as_ptr()) };
+    }
+}
+impl<'a> Theory<'a> for DynamicTheory {
+    fn register<C>(&mut self, ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        unsafe { (self.functions.register)(self.theory.as_ptr(), ctl.ctl.as_ptr()) }
+    }
+    fn rewrite_statement(
+        &mut self,
+        stm: &ast::Statement,
+        builder: &mut ast::ProgramBuilder,
+    ) -> bool {
+        unsafe {
+            (self.functions.rewrite_ast)(
+                self.theory.as_ptr(),
+                stm.ast.ptr.as_ptr(),
+                unsafe_add_ast,
+                builder.theref as *mut clingo_program_builder_t as *mut c_void,
+            )
+        }
+    }
+    fn prepare<C>(&mut self, ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        unsafe { (self.functions.prepare)(self.theory.as_ptr(), ctl.ctl.as_ptr()) }
+    }
+    fn register_options(&mut self, options: &mut Options) -> bool {
+        unsafe {
+            (self.functions.register_options)(
+                self.theory.as_ptr(),
+                &mut options.0 as *mut clingo_options_t,
+            )
+        }
+    }
+    fn validate_options(&mut self) -> bool {
+        unsafe { (self.functions.validate_options)(self.theory.as_ptr()) }
+    }
+    fn on_model(&mut self, model: &mut Model) -> bool {
+        unsafe { (self.functions.on_model)(self.theory.as_ptr(), &mut model.0) }
+    }
+    fn on_statistics(&mut self, step: &mut Statistics, akku: &mut Statistics) -> bool {
+        unsafe { (self.functions.on_statistics)(self.theory.as_ptr(), &mut step.0, &mut akku.0) }
+    }
+    fn lookup_symbol(&mut self, symbol: Symbol, index: &mut usize) -> bool {
+        unsafe { (self.functions.lookup_symbol)(self.theory.as_ptr(), symbol.0, index) }
+    }
+    fn get_symbol(&mut self, index: usize) -> Symbol {
+        Symbol(unsafe { (self.functions.get_symbol)(self.theory.as_ptr(), index) })
+    }
+    fn assignment(
+        &'a self,
+        Id(thread_id): Id,
+    ) -> Box<dyn Iterator<Item = (Symbol, TheoryValue)> + 'a> {
+        let theory = self.theory.as_ptr();
+        let mut values = vec![];
+        let mut index = 0;
+        unsafe {
+            (self.functions.assignment_begin)(theory, thread_id, &mut index);
+            while (self.functions.assignment_next)(theory, thread_id, &mut index) {
+                if (self.functions.assignment_has_value)(theory, thread_id, index) {
+                    let symbol = Symbol((self.functions.get_symbol)(theory, index));
+                    values.push((symbol, self.value(thread_id, index)));
+                }
+            }
+        }
+        Box::new(values.into_iter())
+    }
+    fn configure(&mut self, key: &str, value: &str) -> bool {
+        let (key, value) = match (CString::new(key), CString::new(value)) {
+            (Ok(key), Ok(value)) => (key, value),
+            _ => return false,
+        };
+        unsafe { (self.functions.configure)(self.theory.as_ptr(), key.as_ptr(), value.as_ptr()) }
+    }
+}
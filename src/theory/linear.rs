@@ -0,0 +1,651 @@
+use super::{IntegerVariables, Theory, TheoryValue};
+use crate::propagator::{Clause, ClauseGenerator, LazyClausePropagator, Propagation};
+use crate::{
+    ast, report_error, theory, Assignment, ClingoError, ControlCtx, GenericControl, Id, Model,
+    Options, PropagateControl, PropagateInit, PropagatorCheckMode, SolverLiteral, Statistics,
+    Symbol, TheoryAtomView, TheoryTermView,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Theory definition of linear constraints `&sum{ a*x; ... } op k` and domains `&dom{ l..u } = x`.
+pub const THEORY: &str = "#theory csp {
+    sum_term { - : 3, unary; * : 2, binary, left; + : 1, binary, left; - : 1, binary, left; .. : 0, binary, left };
+    &sum/0 : sum_term, {<=, >=, <, >, =, !=}, sum_term, any;
+    &dom/0 : sum_term, {=}, sum_term, head
+}.";
+
+/// Default lower bound of integer variables without `&dom` atom.
+pub const DEFAULT_MIN: i64 = -1000;
+/// Default upper bound of integer variables without `&dom` atom.
+pub const DEFAULT_MAX: i64 = 1000;
+
+/// Linear term `a1*x1 + ... + an*xn + constant`.
+#[derive(Debug, Clone, Default)]
+struct LinearTerm {
+    terms: Vec<(i64, Symbol)>,
+    constant: i64,
+}
+impl LinearTerm {
+    fn parse(term: &TheoryTermView) -> Result<LinearTerm, ClingoError> {
+        let error = || ClingoError::new_external("Invalid linear term");
+        if let Ok(symbol) = term.to_symbol() {
+            return Ok(match symbol.number() {
+                Ok(number) => LinearTerm {
+                    terms: vec![],
+                    constant: number.into(),
+                },
+                Err(_) => LinearTerm {
+                    terms: vec![(1, symbol)],
+                    constant: 0,
+                },
+            });
+        }
+        match term {
+            TheoryTermView::Function(name, arguments) => match (name.as_str(), &arguments[..]) {
+                ("-", [x]) => LinearTerm::parse(x)?.scale(-1),
+                ("+", [x, y]) => LinearTerm::parse(x)?.add(LinearTerm::parse(y)?),
+                ("-", [x, y]) => LinearTerm::parse(x)?.add(LinearTerm::parse(y)?.scale(-1)?),
+                ("*", [x, y]) => {
+                    let (x, y) = (LinearTerm::parse(x)?, LinearTerm::parse(y)?);
+                    match (x.terms.is_empty(), y.terms.is_empty()) {
+                        (true, _) => y.scale(x.constant),
+                        (_, true) => x.scale(y.constant),
+                        _ => Err(error()),
+                    }
+                }
+                _ => Err(error()),
+            },
+            _ => Err(error()),
+        }
+    }
+    /// Multiply the term by the given factor.
+    ///
+    /// **Errors** if a coefficient or the constant overflows
+    fn scale(mut self, factor: i64) -> Result<LinearTerm, ClingoError> {
+        for (coefficient, _) in &mut self.terms {
+            *coefficient = coefficient.checked_mul(factor).ok_or_else(overflow)?;
+        }
+        self.constant = self.constant.checked_mul(factor).ok_or_else(overflow)?;
+        Ok(self)
+    }
+    /// Add the given term.
+    ///
+    /// **Errors** if the constant overflows
+    fn add(mut self, other: LinearTerm) -> Result<LinearTerm, ClingoError> {
+        self.terms.extend(other.terms);
+        self.constant = self
+            .constant
+            .checked_add(other.constant)
+            .ok_or_else(overflow)?;
+        Ok(self)
+    }
+}
+
+fn overflow() -> ClingoError {
+    ClingoError::new_external("Integer overflow in linear constraint")
+}
+
+/// Integer variable in order encoding.
+#[derive(Debug, Clone)]
+struct Variable {
+    min: i64,
+    max: i64,
+    /// The literal at index `i` holds if and only if the value is at most `min + i`.
+    literals: Vec<SolverLiteral>,
+}
+impl Variable {
+    /// Get the literal for `x <= value`, if `min <= value < max`.
+    fn literal(&self, value: i64) -> Option<SolverLiteral> {
+        if value < self.min || value >= self.max {
+            return None;
+        }
+        Some(self.literals[(value - self.min) as usize])
+    }
+    /// Count the leading order literals satisfying the predicate.
+    fn count(
+        &self,
+        predicate: impl Fn(SolverLiteral) -> Result<bool, ClingoError>,
+    ) -> Result<i64, ClingoError> {
+        let (mut low, mut high) = (0, self.literals.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if predicate(self.literals[middle])? {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low as i64)
+    }
+    /// Get the lower bound w.r.t. the given assignment.
+    fn lower(&self, assignment: &Assignment) -> Result<i64, ClingoError> {
+        Ok(self.min + self.count(|literal| assignment.is_false(literal))?)
+    }
+    /// Get the upper bound w.r.t. the given assignment.
+    fn upper(&self, assignment: &Assignment) -> Result<i64, ClingoError> {
+        Ok(self.min + self.count(|literal| Ok(!assignment.is_true(literal)?))?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relation {
+    LessEqual,
+    NotEqual,
+}
+
+/// Constraint `a1*x1 + ... + an*xn relation bound` that has to hold if `literal` is true.
+#[derive(Debug, Clone)]
+struct Constraint {
+    literal: SolverLiteral,
+    terms: Vec<(i64, usize)>,
+    relation: Relation,
+    bound: i64,
+}
+
+#[derive(Debug)]
+struct State {
+    domain: (i64, i64),
+    symbols: Vec<Symbol>,
+    index: HashMap<Symbol, usize>,
+    domains: Vec<(i64, i64)>,
+    variables: Vec<Variable>,
+    constraints: Vec<Constraint>,
+    watches: HashMap<SolverLiteral, Vec<usize>>,
+    values: Vec<Vec<i64>>,
+}
+impl IntegerVariables for State {
+    fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+    fn index(&self) -> &HashMap<Symbol, usize> {
+        &self.index
+    }
+}
+impl Default for State {
+    fn default() -> Self {
+        State {
+            domain: (DEFAULT_MIN, DEFAULT_MAX),
+            symbols: vec![],
+            index: HashMap::new(),
+            domains: vec![],
+            variables: vec![],
+            constraints: vec![],
+            watches: HashMap::new(),
+            values: vec![],
+        }
+    }
+}
+impl State {
+    fn variable(&mut self, symbol: Symbol) -> usize {
+        if let Some(index) = self.index.get(&symbol) {
+            return *index;
+        }
+        self.symbols.push(symbol);
+        self.domains.push(self.domain);
+        self.index.insert(symbol, self.symbols.len() - 1);
+        self.symbols.len() - 1
+    }
+    /// Add the constraint `term relation 0` for the given literal.
+    ///
+    /// **Errors** if the coefficients of a variable overflow
+    fn add_constraint(
+        &mut self,
+        literal: SolverLiteral,
+        term: LinearTerm,
+        relation: Relation,
+    ) -> Result<(), ClingoError> {
+        let mut coefficients: HashMap<usize, i64> = HashMap::new();
+        for (coefficient, symbol) in term.terms {
+            let sum = coefficients.entry(self.variable(symbol)).or_default();
+            *sum = sum.checked_add(coefficient).ok_or_else(overflow)?;
+        }
+        let mut terms: Vec<(i64, usize)> = coefficients
+            .into_iter()
+            .filter(|(_, coefficient)| *coefficient != 0)
+            .map(|(variable, coefficient)| (coefficient, variable))
+            .collect();
+        terms.sort_by_key(|(_, variable)| *variable);
+        self.constraints.push(Constraint {
+            literal,
+            terms,
+            relation,
+            bound: term.constant.checked_neg().ok_or_else(overflow)?,
+        });
+        Ok(())
+    }
+    /// Add the constraints of a `&sum` atom.
+    fn add_sum(&mut self, init: &PropagateInit, atom: &TheoryAtomView) -> Result<(), ClingoError> {
+        let mut term = LinearTerm::default();
+        for element in &atom.elements {
+            if !element.condition.is_empty() {
+                return Err(ClingoError::new_external(
+                    "Conditional linear constraint elements are not supported",
+                ));
+            }
+            for argument in element.tuple.iter().take(1) {
+                term = term.add(LinearTerm::parse(argument)?)?;
+            }
+        }
+        let (relation, guard) = match &atom.guard {
+            Some((relation, guard)) => (relation.as_str(), LinearTerm::parse(guard)?),
+            None => return Err(ClingoError::new_external("Invalid linear constraint")),
+        };
+        let term = term.add(guard.scale(-1)?)?;
+        let literal = init.solver_literal(atom.literal)?;
+        // all relations are normalized to `term <= 0` or `term != 0`
+        let less_equal = |factor: i64, offset: i64| -> Result<LinearTerm, ClingoError> {
+            let mut term = term.clone().scale(factor)?;
+            term.constant = term.constant.checked_add(offset).ok_or_else(overflow)?;
+            Ok(term)
+        };
+        let (t, f) = (literal, literal.negate());
+        match relation {
+            "<=" => {
+                self.add_constraint(t, less_equal(1, 0)?, Relation::LessEqual)?;
+                self.add_constraint(f, less_equal(-1, 1)?, Relation::LessEqual)?;
+            }
+            "<" => {
+                self.add_constraint(t, less_equal(1, 1)?, Relation::LessEqual)?;
+                self.add_constraint(f, less_equal(-1, 0)?, Relation::LessEqual)?;
+            }
+            ">=" => {
+                self.add_constraint(t, less_equal(-1, 0)?, Relation::LessEqual)?;
+                self.add_constraint(f, less_equal(1, 1)?, Relation::LessEqual)?;
+            }
+            ">" => {
+                self.add_constraint(t, less_equal(-1, 1)?, Relation::LessEqual)?;
+                self.add_constraint(f, less_equal(1, 0)?, Relation::LessEqual)?;
+            }
+            "=" | "!=" => {
+                let (equal, unequal) = if relation == "=" { (t, f) } else { (f, t) };
+                self.add_constraint(equal, less_equal(1, 0)?, Relation::LessEqual)?;
+                self.add_constraint(equal, less_equal(-1, 0)?, Relation::LessEqual)?;
+                self.add_constraint(unequal, less_equal(1, 0)?, Relation::NotEqual)?;
+            }
+            _ => return Err(ClingoError::new_external("Invalid linear constraint")),
+        }
+        Ok(())
+    }
+    /// Restrict the domain of a variable by a `&dom` atom.
+    fn add_domain(
+        &mut self,
+        init: &PropagateInit,
+        atom: &TheoryAtomView,
+    ) -> Result<(), ClingoError> {
+        let error = || ClingoError::new_external("Invalid domain");
+        let literal = init.solver_literal(atom.literal)?;
+        if !init.assignment()?.is_fixed(literal)? || !init.assignment()?.is_true(literal)? {
+            return Err(ClingoError::new_external("Domains must be facts"));
+        }
+        let variable = match &atom.guard {
+            Some((_, guard)) => guard.to_symbol()?,
+            None => return Err(error()),
+        };
+        let (mut min, mut max) = (i64::MAX, i64::MIN);
+        for element in &atom.elements {
+            let (low, high) = match element.tuple.first() {
+                Some(TheoryTermView::Function(name, arguments)) if name == ".." => {
+                    match &arguments[..] {
+                        [low, high] => (low.to_symbol()?.number()?, high.to_symbol()?.number()?),
+                        _ => return Err(error()),
+                    }
+                }
+                Some(term) => {
+                    let value = term.to_symbol()?.number()?;
+                    (value, value)
+                }
+                None => return Err(error()),
+            };
+            min = min.min(low.into());
+            max = max.max(high.into());
+        }
+        let index = self.variable(variable);
+        let (low, high) = self.domains[index];
+        self.domains[index] = (low.max(min), high.min(max));
+        Ok(())
+    }
+    /// Propagate a constraint `term <= bound` whose literal is true.
+    ///
+    /// **Returns** clauses strengthening the bounds of the variables or a conflict
+    fn propagate_less_equal(
+        &self,
+        assignment: &Assignment,
+        constraint: &Constraint,
+    ) -> Result<Vec<Clause>, ClingoError> {
+        let mut minimums = vec![];
+        let mut bounds = vec![];
+        let mut reasons = vec![];
+        for &(coefficient, index) in &constraint.terms {
+            let variable = &self.variables[index];
+            let (lower, upper) = (variable.lower(assignment)?, variable.upper(assignment)?);
+            // the literal in the reason clause is false w.r.t. the assignment
+            if coefficient > 0 {
+                minimums.push(i128::from(coefficient) * i128::from(lower));
+                reasons.push(variable.literal(lower - 1));
+            } else {
+                minimums.push(i128::from(coefficient) * i128::from(upper));
+                reasons.push(variable.literal(upper).map(SolverLiteral::negate));
+            }
+            bounds.push((lower, upper));
+        }
+        let reason = |skip: Option<usize>| -> Vec<SolverLiteral> {
+            let mut clause = vec![constraint.literal.negate()];
+            for (i, literal) in reasons.iter().enumerate() {
+                if Some(i) != skip {
+                    clause.extend(literal);
+                }
+            }
+            clause
+        };
+        let minimum: i128 = minimums.iter().sum();
+        let bound = i128::from(constraint.bound);
+        if minimum > bound {
+            return Ok(vec![Clause::learnt(reason(None))]);
+        }
+        let mut clauses = vec![];
+        for (i, &(coefficient, index)) in constraint.terms.iter().enumerate() {
+            let variable = &self.variables[index];
+            let slack = bound - (minimum - minimums[i]);
+            let (lower, upper) = bounds[i];
+            if coefficient > 0 {
+                let value = slack.div_euclid(i128::from(coefficient));
+                if value < i128::from(upper) {
+                    let mut clause = reason(Some(i));
+                    clause.extend(variable.literal(value as i64));
+                    clauses.push(Clause::learnt(clause));
+                }
+            } else {
+                let value = -slack.div_euclid(-i128::from(coefficient));
+                if value > i128::from(lower) {
+                    let mut clause = reason(Some(i));
+                    clause.extend(
+                        variable
+                            .literal(value as i64 - 1)
+                            .map(SolverLiteral::negate),
+                    );
+                    clauses.push(Clause::learnt(clause));
+                }
+            }
+        }
+        Ok(clauses)
+    }
+    /// Check a constraint `term != bound` whose literal is true w.r.t. a total assignment.
+    ///
+    /// **Returns** a conflict if the constraint is violated
+    fn check_not_equal(
+        &self,
+        assignment: &Assignment,
+        constraint: &Constraint,
+    ) -> Result<Option<Clause>, ClingoError> {
+        let mut sum = 0;
+        let mut clause = vec![constraint.literal.negate()];
+        for &(coefficient, index) in &constraint.terms {
+            let variable = &self.variables[index];
+            let value = variable.lower(assignment)?;
+            sum += i128::from(coefficient) * i128::from(value);
+            clause.extend(variable.literal(value - 1));
+            clause.extend(variable.literal(value).map(SolverLiteral::negate));
+        }
+        if sum == i128::from(constraint.bound) {
+            Ok(Some(Clause::learnt(clause)))
+        } else {
+            Ok(None)
+        }
+    }
+    /// Propagate the given constraints whose literals are true.
+    fn propagate(
+        &self,
+        assignment: &Assignment,
+        constraints: impl IntoIterator<Item = usize>,
+        total: bool,
+    ) -> Result<Vec<Clause>, ClingoError> {
+        let mut clauses = vec![];
+        for index in constraints {
+            let constraint = &self.constraints[index];
+            if !assignment.is_true(constraint.literal)? {
+                continue;
+            }
+            match constraint.relation {
+                Relation::LessEqual => {
+                    clauses.extend(self.propagate_less_equal(assignment, constraint)?)
+                }
+                Relation::NotEqual if total => {
+                    clauses.extend(self.check_not_equal(assignment, constraint)?)
+                }
+                Relation::NotEqual => {}
+            }
+        }
+        Ok(clauses)
+    }
+    /// Initialize the constraints, a conflict makes the problem unsatisfiable.
+    fn init(&mut self, init: &mut PropagateInit) -> Result<(), ClingoError> {
+        *self = State {
+            domain: self.domain,
+            ..State::default()
+        };
+        let atoms = init.theory_atoms()?.views()?;
+        for atom in atoms.iter().filter(|atom| atom.name() == "dom") {
+            self.add_domain(init, atom)?;
+        }
+        for atom in atoms.iter().filter(|atom| atom.name() == "sum") {
+            self.add_sum(init, atom)?;
+        }
+        for &(min, max) in &self.domains {
+            if min > max {
+                init.add_clause(&[])?;
+                return Ok(());
+            }
+            let mut literals = vec![];
+            for _ in min..max {
+                literals.push(init.add_literal(true)?);
+            }
+            for pair in literals.windows(2) {
+                if !init.add_clause(&[pair[0].negate(), pair[1]])? {
+                    return Ok(());
+                }
+            }
+            self.variables.push(Variable { min, max, literals });
+        }
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let mut watches = vec![constraint.literal];
+            if constraint.relation == Relation::LessEqual {
+                for &(_, variable) in &constraint.terms {
+                    for &literal in &self.variables[variable].literals {
+                        watches.push(literal);
+                        watches.push(literal.negate());
+                    }
+                }
+            }
+            for literal in watches {
+                self.watches.entry(literal).or_default().push(index);
+            }
+        }
+        let literals: Vec<SolverLiteral> = self.watches.keys().copied().collect();
+        for literal in literals {
+            init.add_watch(literal)?;
+        }
+        self.values = vec![vec![]; init.number_of_threads()];
+        // propagate constraints holding unconditionally
+        loop {
+            let clauses = self.propagate(init.assignment()?, 0..self.constraints.len(), false)?;
+            if clauses.is_empty() {
+                return Ok(());
+            }
+            for clause in clauses {
+                if !init.add_clause(&clause.literals)? || !init.propagate()? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Clause generator for linear constraints.
+struct LinearConstraintGenerator {
+    state: Arc<Mutex<State>>,
+}
+impl LinearConstraintGenerator {
+    fn report(result: Result<Vec<Clause>, ClingoError>) -> Propagation {
+        match result {
+            Ok(clauses) => Propagation::Clauses(clauses),
+            Err(error) => {
+                report_error(&error);
+                Propagation::default()
+            }
+        }
+    }
+}
+impl ClauseGenerator for LinearConstraintGenerator {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        match state.init(init) {
+            Ok(()) => true,
+            Err(error) => {
+                report_error(&error);
+                false
+            }
+        }
+    }
+    fn propagate(&mut self, control: &PropagateControl, changes: &[SolverLiteral]) -> Propagation {
+        let state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return Propagation::default(),
+        };
+        let constraints: BTreeSet<usize> = changes
+            .iter()
+            .filter_map(|literal| state.watches.get(literal))
+            .flatten()
+            .copied()
+            .collect();
+        Self::report(
+            control
+                .assignment()
+                .and_then(|assignment| state.propagate(assignment, constraints, false)),
+        )
+    }
+    fn check(&mut self, control: &PropagateControl) -> Propagation {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return Propagation::default(),
+        };
+        let state = &mut *state;
+        let result = control.assignment().and_then(|assignment| {
+            let clauses = state.propagate(assignment, 0..state.constraints.len(), true)?;
+            if clauses.is_empty() {
+                state.values[control.thread_id() as usize] = state
+                    .variables
+                    .iter()
+                    .map(|variable| variable.lower(assignment))
+                    .collect::<Result<_, _>>()?;
+            }
+            Ok(clauses)
+        });
+        Self::report(result)
+    }
+}
+
+/// A theory for linear integer constraints of form `&sum{ a1*x1; ...; an*xn } op k`.
+///
+/// The relation `op` is one of `<=`, `>=`, `<`, `>`, `=` and `!=`, and the terms on either side
+/// may mix integer variables and constants.
+/// A constraint holds if and only if its atom is true.
+///
+/// Each variable takes values in `[DEFAULT_MIN, DEFAULT_MAX]` unless restricted by a fact of form
+/// `&dom{ l..u } = x`.
+/// Variables are order encoded and propagated on their bounds generating clauses lazily.
+/// Models are extended with atoms `csp(x,value)` holding the integer values of the variables.
+#[derive(Debug, Clone, Default)]
+pub struct LinearConstraints {
+    state: Arc<Mutex<State>>,
+}
+impl LinearConstraints {
+    /// Create a linear constraint theory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Create a linear constraint theory with the given default domain of variables.
+    pub fn with_domain(min: i64, max: i64) -> Self {
+        let theory = Self::default();
+        if let Ok(mut state) = theory.state.lock() {
+            state.domain = (min, max);
+        }
+        theory
+    }
+    /// Get the values of the variables in the last model found by the given solver thread.
+    pub fn values(&self, thread_id: u32) -> Vec<(Symbol, i64)> {
+        match self.state.lock() {
+            Ok(state) => match state.values.get(thread_id as usize) {
+                Some(values) => state.symbols.iter().copied().zip(values.clone()).collect(),
+                None => vec![],
+            },
+            Err(_) => vec![],
+        }
+    }
+}
+impl<'a> Theory<'a> for LinearConstraints {
+    fn register<C>(&mut self, ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        let propagator = LazyClausePropagator::new(LinearConstraintGenerator {
+            state: self.state.clone(),
+        });
+        theory::register_propagator(ctl, THEORY, propagator, PropagatorCheckMode::Total)
+    }
+    fn rewrite_statement(
+        &mut self,
+        stm: &ast::Statement,
+        builder: &mut ast::ProgramBuilder,
+    ) -> bool {
+        builder.add(stm).is_ok()
+    }
+    fn prepare<C>(&mut self, _ctl: &mut GenericControl<C>) -> bool
+    where
+        C: ControlCtx,
+    {
+        true
+    }
+    fn register_options(&mut self, _options: &mut Options) -> bool {
+        true
+    }
+    fn validate_options(&mut self) -> bool {
+        true
+    }
+    fn on_model(&mut self, model: &mut Model) -> bool {
+        theory::extend_model(model, "csp", |thread_id| self.values(thread_id))
+    }
+    fn on_statistics(&mut self, _step: &mut Statistics, _akku: &mut Statistics) -> bool {
+        true
+    }
+    fn lookup_symbol(&mut self, symbol: Symbol, index: &mut usize) -> bool {
+        theory::lookup_symbol(&self.state, symbol, index)
+    }
+    fn get_symbol(&mut self, index: usize) -> Symbol {
+        theory::get_symbol(&self.state, index)
+    }
+    fn assignment(
+        &'a self,
+        Id(thread_id): Id,
+    ) -> Box<dyn Iterator<Item = (Symbol, TheoryValue)> + 'a> {
+        theory::assignment(self.values(thread_id))
+    }
+    fn configure(&mut self, key: &str, value: &str) -> bool {
+        let value = match value.parse() {
+            Ok(value) => value,
+            Err(_) => return false,
+        };
+        match self.state.lock() {
+            Ok(mut state) => match key {
+                "min-int" => state.domain.0 = value,
+                "max-int" => state.domain.1 = value,
+                _ => return false,
+            },
+            Err(_) => return false,
+        }
+        true
+    }
+}
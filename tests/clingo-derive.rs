@@ -1,16 +1,18 @@
 #[cfg(feature = "derive")]
 pub mod derive {
     use clingo::ClingoError;
+    use clingo::FromSymbol;
     use clingo::Symbol;
     use clingo::ToSymbol;
+    use clingo::{control, Part, ShowType, Signature, SolveMode};
 
-    #[derive(Copy, Clone, ToSymbol)]
+    #[derive(Copy, Clone, Debug, PartialEq, ToSymbol, FromSymbol)]
     struct Test;
 
-    #[derive(Copy, Clone, ToSymbol)]
+    #[derive(Copy, Clone, Debug, PartialEq, ToSymbol, FromSymbol)]
     struct Test2;
 
-    #[derive(ToSymbol)]
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
     struct Bla<'a> {
         test: Test,
         s: String,
@@ -20,10 +22,10 @@ pub mod derive {
         str2: &'a str,
     }
 
-    #[derive(ToSymbol)]
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
     struct Blub(Test, Test2);
 
-    #[derive(ToSymbol)]
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
     pub enum Signs<'a> {
         Minus,
         Mix(u32, String),
@@ -32,6 +34,69 @@ pub mod derive {
         Strange { sds: &'a str },
     }
 
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    #[clingo(name = "edge", arity_check)]
+    struct Connection {
+        from: Node,
+        to: Node,
+        #[clingo(skip)]
+        visited: bool,
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    #[clingo(transparent)]
+    struct Node(u32);
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    struct Reading {
+        #[clingo(string)]
+        value: f64,
+        unit: Unit,
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    enum Unit {
+        #[clingo(name = "c")]
+        Celsius,
+        #[clingo(name = "k", arity_check)]
+        Kelvin,
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    enum Move {
+        #[clingo(name = "move")]
+        Step(u32),
+        #[clingo(name = "move")]
+        Jump(u32, u32),
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    enum Item {
+        #[clingo(name = "item")]
+        Count(u32),
+        #[clingo(name = "item")]
+        Label(String),
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    #[clingo(name = "pos", allow_extra_args)]
+    struct Position(u32);
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    struct Route {
+        stops: Vec<Node>,
+        via: Option<Node>,
+    }
+
+    #[derive(Debug, PartialEq, ToSymbol, FromSymbol)]
+    enum Value {
+        #[clingo(transparent)]
+        Number(i32),
+        #[clingo(transparent)]
+        Text(String),
+        Missing,
+    }
+
     #[test]
     fn to_symbol() {
         let t = Test;
@@ -80,4 +145,218 @@ pub mod derive {
         let s2 = clingo::parse_term("plus(3,(4,\"HHHR\"))").unwrap();
         assert_eq!(s1, s2);
     }
+
+    #[test]
+    fn from_symbol() {
+        let s = clingo::parse_term("test").unwrap();
+        assert_eq!(Test::from_symbol(s).unwrap(), Test);
+        let s = clingo::parse_term("test_2").unwrap();
+        assert!(Test::from_symbol(s).is_err());
+        let s = clingo::parse_term("test(1)").unwrap();
+        assert!(Test::from_symbol(s).is_err());
+        let s = clingo::parse_term("-test").unwrap();
+        assert!(Test::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("bla(test,\"bala\",1,(47,\"bum\"),false,\"ddbb\")").unwrap();
+        let bla = Bla::from_symbol(s).unwrap();
+        assert_eq!(
+            bla,
+            Bla {
+                test: Test,
+                s: "bala".to_string(),
+                u_32: 1,
+                tup: (47, "bum".to_string()),
+                str1: false,
+                str2: "ddbb",
+            }
+        );
+        assert_eq!(bla.symbol().unwrap(), s);
+        let s = clingo::parse_term("bla(test,\"bala\",-1,(47,\"bum\"),false,\"ddbb\")").unwrap();
+        assert!(Bla::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("blub(test,test_2)").unwrap();
+        assert_eq!(Blub::from_symbol(s).unwrap(), Blub(Test, Test2));
+        let s = clingo::parse_term("blub(test_2,test)").unwrap();
+        assert!(Blub::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("minus").unwrap();
+        assert_eq!(Signs::from_symbol(s).unwrap(), Signs::Minus);
+
+        let s = clingo::parse_term("mix(42,\"bla\")").unwrap();
+        assert_eq!(
+            Signs::from_symbol(s).unwrap(),
+            Signs::Mix(42, "bla".to_string())
+        );
+
+        let s = clingo::parse_term("tup((42,\"bla\"))").unwrap();
+        assert_eq!(
+            Signs::from_symbol(s).unwrap(),
+            Signs::Tup((42, "bla".to_string()))
+        );
+
+        let s = clingo::parse_term("plus(3,(4,\"HHHR\"))").unwrap();
+        assert_eq!(
+            Signs::from_symbol(s).unwrap(),
+            Signs::Plus {
+                uuu: 3,
+                tup: (4, "HHHR".to_string()),
+            }
+        );
+
+        let s = clingo::parse_term("strange(\"x\")").unwrap();
+        assert_eq!(Signs::from_symbol(s).unwrap(), Signs::Strange { sds: "x" });
+
+        let s = clingo::parse_term("plus(3)").unwrap();
+        assert!(Signs::from_symbol(s).is_err());
+        let s = clingo::parse_term("42").unwrap();
+        assert!(Signs::from_symbol(s).is_err());
+    }
+
+    #[test]
+    fn attributes() {
+        let edge = Connection {
+            from: Node(1),
+            to: Node(2),
+            visited: true,
+        };
+        let s = edge.symbol().unwrap();
+        assert_eq!(s, clingo::parse_term("edge(1,2)").unwrap());
+        assert_eq!(
+            Connection::from_symbol(s).unwrap(),
+            Connection {
+                from: Node(1),
+                to: Node(2),
+                visited: false,
+            }
+        );
+        let s = clingo::parse_term("edge(1,2,3)").unwrap();
+        assert!(Connection::from_symbol(s).is_err());
+        let s = clingo::parse_term("connection(1,2)").unwrap();
+        assert!(Connection::from_symbol(s).is_err());
+
+        let s = Node(7).symbol().unwrap();
+        assert_eq!(s, Symbol::create_number(7));
+        assert_eq!(Node::from_symbol(s).unwrap(), Node(7));
+
+        let reading = Reading {
+            value: 21.5,
+            unit: Unit::Celsius,
+        };
+        let s = reading.symbol().unwrap();
+        assert_eq!(s, clingo::parse_term("reading(\"21.5\",c)").unwrap());
+        assert_eq!(Reading::from_symbol(s).unwrap(), reading);
+        let s = clingo::parse_term("reading(\"warm\",c)").unwrap();
+        assert!(Reading::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("c(1)").unwrap();
+        assert!(Unit::from_symbol(s).is_err());
+        let s = clingo::parse_term("k(1)").unwrap();
+        assert!(Unit::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("move(1)").unwrap();
+        assert_eq!(Move::from_symbol(s).unwrap(), Move::Step(1));
+        let s = clingo::parse_term("move(1,2)").unwrap();
+        assert_eq!(Move::from_symbol(s).unwrap(), Move::Jump(1, 2));
+        let s = clingo::parse_term("move(1,2,3)").unwrap();
+        assert!(Move::from_symbol(s).is_err());
+
+        let s = clingo::parse_term("item(3)").unwrap();
+        assert_eq!(Item::from_symbol(s).unwrap(), Item::Count(3));
+        let s = clingo::parse_term("item(\"x\")").unwrap();
+        assert_eq!(Item::from_symbol(s).unwrap(), Item::Label("x".to_string()));
+        let s = clingo::parse_term("item(x)").unwrap();
+        assert!(Item::from_symbol(s).is_err());
+
+        let s = Position(1).symbol().unwrap();
+        assert_eq!(s, clingo::parse_term("pos(1)").unwrap());
+        assert_eq!(Position::from_symbol(s).unwrap(), Position(1));
+        let s = clingo::parse_term("pos(1,2)").unwrap();
+        assert_eq!(Position::from_symbol(s).unwrap(), Position(1));
+        let s = clingo::parse_term("pos").unwrap();
+        assert!(Position::from_symbol(s).is_err());
+
+        let s = Value::Number(3).symbol().unwrap();
+        assert_eq!(s, Symbol::create_number(3));
+        assert_eq!(Value::from_symbol(s).unwrap(), Value::Number(3));
+        let s = Value::Text("x".to_string()).symbol().unwrap();
+        assert_eq!(s, Symbol::create_string("x").unwrap());
+        assert_eq!(Value::from_symbol(s).unwrap(), Value::Text("x".to_string()));
+        let s = Value::Missing.symbol().unwrap();
+        assert_eq!(s, clingo::parse_term("missing").unwrap());
+        assert_eq!(Value::from_symbol(s).unwrap(), Value::Missing);
+        let s = clingo::parse_term("other").unwrap();
+        assert!(Value::from_symbol(s).is_err());
+    }
+
+    #[test]
+    fn collections() {
+        let route = Route {
+            stops: vec![Node(1), Node(2)],
+            via: None,
+        };
+        let s = route.symbol().unwrap();
+        assert_eq!(s, clingo::parse_term("route((1,2),none)").unwrap());
+        assert_eq!(Route::from_symbol(s).unwrap(), route);
+
+        let s = clingo::parse_term("route((),some(3))").unwrap();
+        assert_eq!(
+            Route::from_symbol(s).unwrap(),
+            Route {
+                stops: vec![],
+                via: Some(Node(3)),
+            }
+        );
+    }
+
+    #[test]
+    fn decode_model() {
+        let mut ctl = control(vec![]).unwrap();
+        ctl.add("base", &[], "edge(1,2). edge(2,3). edge(3). node(1..3).")
+            .unwrap();
+        ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+        let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+        handle.resume().unwrap();
+        let model = handle.model().unwrap().unwrap();
+
+        let edge = Signature::new("edge", 2, true).unwrap();
+        let mut edges: Vec<Connection> = model.decode(&edge).unwrap();
+        edges.sort_by_key(|edge| edge.from.0);
+        assert_eq!(
+            edges,
+            vec![
+                Connection {
+                    from: Node(1),
+                    to: Node(2),
+                    visited: false,
+                },
+                Connection {
+                    from: Node(2),
+                    to: Node(3),
+                    visited: false,
+                },
+            ]
+        );
+
+        let view = model.view(ShowType::SHOWN).unwrap();
+        let signatures = view.signatures();
+        assert_eq!(signatures.len(), 3);
+        assert!(signatures.contains(&Signature::new("edge", 1, true).unwrap()));
+        assert_eq!(
+            view.symbols(&Signature::new("node", 1, true).unwrap())
+                .len(),
+            3
+        );
+        assert!(view
+            .symbols(&Signature::new("node", 2, true).unwrap())
+            .is_empty());
+        assert_eq!(view.decode::<Connection>(&edge).unwrap().len(), 2);
+
+        // node(1) is not of the form edge(X,Y)
+        let node = Signature::new("node", 1, true).unwrap();
+        match view.decode::<Connection>(&node) {
+            Err(ClingoError::DecodeError { symbol, .. }) => assert!(symbol.starts_with("node(")),
+            _ => panic!("expected a DecodeError"),
+        }
+        handle.close().unwrap();
+    }
 }
@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use clingo::{ControlCtx, GenericControl, Model, ShowType, SolveMode};
+
+/// Solve and collect the shown symbols of the models accepted by `filter`.
+///
+/// Symbols and models are sorted to compare them independently of the solving order.
+fn solve_with<C: ControlCtx>(
+    ctl: GenericControl<C>,
+    filter: impl Fn(&Model) -> bool,
+) -> (GenericControl<C>, Vec<Vec<String>>) {
+    let mut handle = ctl
+        .solve(SolveMode::YIELD, &[])
+        .expect("Failed to retrieve solve handle.");
+    let mut models = vec![];
+    while let Some(model) = handle.model().unwrap() {
+        if filter(model) {
+            let mut symbols: Vec<String> = model
+                .symbols(ShowType::SHOWN)
+                .unwrap()
+                .iter()
+                .map(|symbol| symbol.to_string())
+                .collect();
+            symbols.sort();
+            models.push(symbols);
+        }
+        handle.resume().unwrap();
+    }
+    let ctl = handle.close().unwrap();
+    models.sort();
+    (ctl, models)
+}
+
+/// Solve and collect the shown symbols of all models.
+pub fn models<C: ControlCtx>(ctl: GenericControl<C>) -> (GenericControl<C>, Vec<Vec<String>>) {
+    solve_with(ctl, |_| true)
+}
+
+/// Solve and collect the shown symbols of the models proven optimal.
+pub fn optimal_models<C: ControlCtx>(
+    ctl: GenericControl<C>,
+) -> (GenericControl<C>, Vec<Vec<String>>) {
+    solve_with(ctl, |model| model.optimality_proven().unwrap())
+}
@@ -0,0 +1,164 @@
+//! A theory plugin exporting the clingo theory ABI with prefix `clingotest`.
+//!
+//! The plugin passes statements through unchanged, counts the models it sees and assigns
+//! `offset + models` to every variable looked up.
+//! It is compiled to a shared library by the `dynamic_theory` test.
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+
+type AstCallback = unsafe extern "C" fn(ast: *mut c_void, data: *mut c_void) -> bool;
+
+#[repr(C)]
+pub struct Value {
+    value_type: c_int,
+    value: ValueUnion,
+}
+#[repr(C)]
+#[allow(dead_code)]
+pub union ValueUnion {
+    int_number: c_int,
+    double_number: f64,
+    symbol: u64,
+}
+
+#[derive(Default)]
+struct State {
+    symbols: Vec<u64>,
+    models: c_int,
+    offset: c_int,
+}
+
+unsafe fn state<'a>(theory: *mut c_void) -> &'a mut State {
+    &mut *(theory as *mut State)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_create(theory: *mut *mut c_void) -> bool {
+    *theory = Box::into_raw(Box::<State>::default()) as *mut c_void;
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_destroy(theory: *mut c_void) -> bool {
+    drop(Box::from_raw(theory as *mut State));
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_register(_theory: *mut c_void, _control: *mut c_void) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_rewrite_ast(
+    _theory: *mut c_void,
+    ast: *mut c_void,
+    add: AstCallback,
+    data: *mut c_void,
+) -> bool {
+    add(ast, data)
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_prepare(_theory: *mut c_void, _control: *mut c_void) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_register_options(
+    _theory: *mut c_void,
+    _options: *mut c_void,
+) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_validate_options(_theory: *mut c_void) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_on_model(theory: *mut c_void, _model: *mut c_void) -> bool {
+    state(theory).models += 1;
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_on_statistics(
+    _theory: *mut c_void,
+    _step: *mut c_void,
+    _akku: *mut c_void,
+) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_lookup_symbol(
+    theory: *mut c_void,
+    symbol: u64,
+    index: *mut usize,
+) -> bool {
+    let state = state(theory);
+    let position = match state.symbols.iter().position(|s| *s == symbol) {
+        Some(position) => position,
+        None => {
+            state.symbols.push(symbol);
+            state.symbols.len() - 1
+        }
+    };
+    // indices start at 1 like in clingo-dl
+    *index = position + 1;
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_get_symbol(theory: *mut c_void, index: usize) -> u64 {
+    state(theory).symbols[index - 1]
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_assignment_begin(
+    _theory: *mut c_void,
+    _thread_id: u32,
+    index: *mut usize,
+) {
+    *index = 0;
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_assignment_next(
+    theory: *mut c_void,
+    _thread_id: u32,
+    index: *mut usize,
+) -> bool {
+    *index += 1;
+    *index <= state(theory).symbols.len()
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_assignment_has_value(
+    _theory: *mut c_void,
+    _thread_id: u32,
+    _index: usize,
+) -> bool {
+    true
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_assignment_get_value(
+    theory: *mut c_void,
+    _thread_id: u32,
+    _index: usize,
+    value: *mut Value,
+) {
+    let state = state(theory);
+    *value = Value {
+        value_type: 0,
+        value: ValueUnion {
+            int_number: state.offset + state.models,
+        },
+    };
+}
+#[no_mangle]
+pub unsafe extern "C" fn clingotest_configure(
+    theory: *mut c_void,
+    key: *const c_char,
+    value: *const c_char,
+) -> bool {
+    let (key, value) = (CStr::from_ptr(key), CStr::from_ptr(value));
+    match (key.to_str(), value.to_str().map(str::parse)) {
+        (Ok("offset"), Ok(Ok(offset))) => {
+            state(theory).offset = offset;
+            true
+        }
+        _ => false,
+    }
+}
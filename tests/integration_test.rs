@@ -1,6 +1,8 @@
 use clingo::ast::*;
 use clingo::*;
 
+mod common;
+
 #[test]
 fn version() {
     let (ma, mi, re) = clingo::version();
@@ -113,6 +115,54 @@ fn backend() {
     }
 }
 #[test]
+fn symbolic_backend() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let p = Symbol::create_function("p", &[Symbol::create_number(1)], true).unwrap();
+    let q = Symbol::create_id("q", true).unwrap();
+    let r = Symbol::create_id("r", true).unwrap();
+    let s = Symbol::create_id("s", true).unwrap();
+    {
+        let mut backend = ctl.symbolic_backend().unwrap();
+        let atom = backend.atom(q).unwrap();
+        assert_eq!(
+            SolverLiteral::from(atom),
+            SolverLiteral::from(backend.atom(q).unwrap())
+        );
+        assert_eq!(
+            backend.literal(SymbolicLiteral::Negative(q)).unwrap(),
+            SolverLiteral::from(atom).negate()
+        );
+        backend.fact(p).unwrap();
+        backend.choice_rule(&[q, s], &[]).unwrap();
+        backend
+            .rule(
+                &[r],
+                &[SymbolicLiteral::Positive(q), SymbolicLiteral::Negative(s)],
+            )
+            .unwrap();
+        backend.rule(&[], &[SymbolicLiteral::Positive(s)]).unwrap();
+    }
+
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    let mut models = vec![];
+    while let Some(model) = handle.model().unwrap() {
+        let mut symbols: Vec<String> = model
+            .symbols(ShowType::SHOWN)
+            .unwrap()
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect();
+        symbols.sort();
+        models.push(symbols);
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+    models.sort();
+    assert_eq!(models, [vec!["p(1)"], vec!["p(1)", "q", "r"]]);
+}
+#[test]
 fn symbols() {
     let number_symbol = Symbol::create_number(42);
     let identifier_symbol = Symbol::create_id("x", true).unwrap();
@@ -125,6 +175,117 @@ fn symbols() {
     assert_eq!(symbols.to_vec(), symbols2);
 }
 #[test]
+fn symbol_conversions() {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+    let v = vec![1, 2, 3];
+    let sym = v.symbol().unwrap();
+    assert_eq!(sym.to_string(), "(1,2,3)");
+    assert_eq!(Vec::<i32>::from_symbol(sym).unwrap(), v);
+    assert_eq!(<[i32; 3]>::from_symbol(sym).unwrap(), [1, 2, 3]);
+    assert!(<[i32; 2]>::from_symbol(sym).is_err());
+    assert_eq!(
+        VecDeque::<i32>::from_symbol(sym).unwrap(),
+        VecDeque::from(v)
+    );
+    assert_eq!(vec!["a"].symbol().unwrap().to_string(), "(\"a\",)");
+    assert_eq!(Vec::<i32>::new().symbol().unwrap().to_string(), "()");
+    assert!(Vec::<i32>::from_symbol(parse_term("f(1,2)").unwrap()).is_err());
+
+    let set: HashSet<i32> = [3, 1, 2].into_iter().collect();
+    let sym = set.symbol().unwrap();
+    assert_eq!(sym.to_string(), "(1,2,3)");
+    assert_eq!(HashSet::<i32>::from_symbol(sym).unwrap(), set);
+    let set: BTreeSet<String> = ["b".to_string(), "a".to_string()].into_iter().collect();
+    assert_eq!(BTreeSet::from_symbol(set.symbol().unwrap()).unwrap(), set);
+    let sym = vec![1, 2, 1].symbol().unwrap();
+    assert!(BTreeSet::<i32>::from_symbol(sym).is_err());
+    assert!(HashSet::<i32>::from_symbol(sym).is_err());
+
+    let map: HashMap<String, u32> = [("y".to_string(), 2), ("x".to_string(), 1)]
+        .into_iter()
+        .collect();
+    let sym = map.symbol().unwrap();
+    assert_eq!(sym.to_string(), "((\"x\",1),(\"y\",2))");
+    assert_eq!(HashMap::<String, u32>::from_symbol(sym).unwrap(), map);
+    let map: BTreeMap<u32, bool> = [(1, true), (2, false)].into_iter().collect();
+    let sym = map.symbol().unwrap();
+    assert_eq!(sym.to_string(), "((1,true),(2,false))");
+    assert_eq!(BTreeMap::from_symbol(sym).unwrap(), map);
+    let sym = vec![(1, true), (1, false)].symbol().unwrap();
+    assert!(BTreeMap::<u32, bool>::from_symbol(sym).is_err());
+    assert!(HashMap::<u32, bool>::from_symbol(sym).is_err());
+
+    let sym = None::<i32>.symbol().unwrap();
+    assert_eq!(sym.to_string(), "none");
+    assert_eq!(Option::<i32>::from_symbol(sym).unwrap(), None);
+    let sym = Some(vec![4]).symbol().unwrap();
+    assert_eq!(sym.to_string(), "some((4,))");
+    assert_eq!(Option::<Vec<i32>>::from_symbol(sym).unwrap(), Some(vec![4]));
+    assert!(Option::<i32>::from_symbol(parse_term("some(1,2)").unwrap()).is_err());
+
+    let ok: Result<u8, String> = Ok(1);
+    let sym = ok.symbol().unwrap();
+    assert_eq!(sym.to_string(), "ok(1)");
+    assert_eq!(Result::<u8, String>::from_symbol(sym).unwrap(), ok);
+    let err: Result<u8, String> = Err("failed".to_string());
+    let sym = err.symbol().unwrap();
+    assert_eq!(sym.to_string(), "error(\"failed\")");
+    assert_eq!(Result::<u8, String>::from_symbol(sym).unwrap(), err);
+
+    let sym = 'x'.symbol().unwrap();
+    assert_eq!(sym.to_string(), "\"x\"");
+    assert_eq!(char::from_symbol(sym).unwrap(), 'x');
+    assert!(char::from_symbol(parse_term("\"xy\"").unwrap()).is_err());
+
+    let sym = 2.5f64.symbol().unwrap();
+    assert_eq!(sym.to_string(), "\"2.5\"");
+    assert_eq!(f64::from_symbol(sym).unwrap(), 2.5);
+    assert!(f64::from_symbol(parse_term("\"x\"").unwrap()).is_err());
+
+    let sym = Box::new(5u16).symbol().unwrap();
+    assert_eq!(Box::<u16>::from_symbol(sym).unwrap(), Box::new(5));
+    let sym = (1, ("a".to_string(), true)).symbol().unwrap();
+    assert_eq!(
+        <(i32, (String, bool))>::from_symbol(sym).unwrap(),
+        (1, ("a".to_string(), true))
+    );
+}
+#[test]
+fn fact_base() {
+    let mut fb = FactBase::new();
+    assert!(fb.insert(&parse_term("b(2)").unwrap()).unwrap());
+    assert!(fb.insert(&parse_term("a(1,2)").unwrap()).unwrap());
+    assert!(fb.insert(&parse_term("b(1)").unwrap()).unwrap());
+    assert!(!fb.insert(&parse_term("b(1)").unwrap()).unwrap());
+    assert_eq!(fb.len(), 3);
+    assert!(fb.contains(&parse_term("b(2)").unwrap()).unwrap());
+    assert_eq!(fb.to_string(), "b(1).\nb(2).\na(1,2).\n");
+
+    let b = Signature::new("b", 1, true).unwrap();
+    assert_eq!(
+        fb.by_signature(&b),
+        vec![parse_term("b(1)").unwrap(), parse_term("b(2)").unwrap()]
+    );
+    assert!(fb
+        .by_signature(&Signature::new("a", 1, true).unwrap())
+        .is_empty());
+
+    assert!(fb.remove(&parse_term("b(2)").unwrap()).unwrap());
+    assert!(!fb.remove(&parse_term("b(2)").unwrap()).unwrap());
+    assert!(!fb.contains(&parse_term("b(2)").unwrap()).unwrap());
+
+    let mut out = Vec::new();
+    fb.write_to(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "b(1).\na(1,2).\n");
+
+    let mut ctl = control(vec![]).unwrap();
+    ctl.add_facts(&fb).unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    let sa = ctl.symbolic_atoms().unwrap();
+    assert_eq!(sa.size().unwrap(), 2);
+}
+#[test]
 fn theory_atoms() {
     let mut ctl = control(vec![]).unwrap();
     ctl.add(
@@ -153,6 +314,584 @@ fn theory_atoms() {
     }
 }
 
+#[test]
+fn theory_atom_views() {
+    let mut ctl = control(vec![]).unwrap();
+    ctl.add(
+        "base",
+        &[],
+        "#theory t {\
+         term   { - : 1, unary; - : 0, binary, left };\
+         &sum/0 : term, {<=, =}, term, body;\
+         &out/0 : term, directive\
+         }.\
+         {p(1..2)}. \
+         &out { (a,[b]) }. \
+         :- &sum { x(1) - y : p(1); 3 : p(2) } <= -4.",
+    )
+    .unwrap();
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts).unwrap();
+
+    let atoms = ctl.theory_atoms().unwrap();
+    let mut views = atoms.views().unwrap();
+    views.sort_by_key(|view| view.name().to_owned());
+    assert_eq!(views.len(), 2);
+
+    let show = &views[0];
+    assert_eq!(show.name(), "out");
+    assert!(show.guard.is_none());
+    assert_eq!(show.elements.len(), 1);
+    let tuple = TheoryTermView::Tuple(vec![
+        TheoryTermView::Symbol("a".into()),
+        TheoryTermView::List(vec![TheoryTermView::Symbol("b".into())]),
+    ]);
+    assert_eq!(show.elements[0].tuple, [tuple]);
+    assert!(show.elements[0].condition.is_empty());
+
+    let sum = &views[1];
+    assert_eq!(sum.name(), "sum");
+    assert_eq!(sum.term, TheoryTermView::Symbol("sum".into()));
+    let (operator, guard) = sum.guard.clone().unwrap();
+    assert_eq!(operator, "<=");
+    assert_eq!(guard.to_symbol().unwrap(), Symbol::create_number(-4));
+    assert_eq!(sum.elements.len(), 2);
+    let difference = &sum.elements[0].tuple[0];
+    assert_eq!(difference.name(), Some("-"));
+    assert_eq!(difference.arguments().len(), 2);
+    let x = Symbol::create_function("x", &[Symbol::create_number(1)], true).unwrap();
+    assert_eq!(difference.arguments()[0].to_symbol().unwrap(), x);
+    assert!(difference.to_symbol().is_err());
+    assert_eq!(sum.elements[0].condition.len(), 1);
+    assert_eq!(sum.elements[1].tuple, [TheoryTermView::Number(3)]);
+}
+
+#[test]
+fn backend_theory_atoms() {
+    let mut ctl = control(vec![]).unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    {
+        let mut backend = ctl.backend().unwrap();
+        let atom = backend.add_atom(None).unwrap();
+        backend.rule(true, &[atom], &[]).unwrap();
+        let condition = backend.add_atom(None).unwrap();
+
+        let sum = backend.theory_term_string("sum").unwrap();
+        let x = backend
+            .theory_term_symbol(Symbol::create_id("x", true).unwrap())
+            .unwrap();
+        let two = backend.theory_term_number(2).unwrap();
+        let product = backend.theory_term_function("*", &[two, x]).unwrap();
+        let three = backend.theory_term_number(3).unwrap();
+        let tuple = backend
+            .theory_term_sequence(TheorySequenceType::Tuple, &[x, three])
+            .unwrap();
+        let list = backend
+            .theory_term_sequence(TheorySequenceType::List, &[x])
+            .unwrap();
+        let set = backend
+            .theory_term_sequence(TheorySequenceType::Set, &[list])
+            .unwrap();
+        let first = backend.theory_element(&[product, set], &[]).unwrap();
+        let second = backend
+            .theory_element(&[tuple], &[condition.into()])
+            .unwrap();
+        backend
+            .theory_atom_with_guard(Some(atom), sum, &[first, second], "<=", three)
+            .unwrap();
+
+        let out = backend.theory_term_string("out").unwrap();
+        let element = backend.theory_element(&[x], &[]).unwrap();
+        backend.theory_atom(None, out, &[element]).unwrap();
+    }
+
+    let atoms = ctl.theory_atoms().unwrap();
+    let mut views = atoms.views().unwrap();
+    views.sort_by_key(|view| view.name().to_owned());
+    assert_eq!(views.len(), 2);
+
+    let out = &views[0];
+    assert_eq!(out.name(), "out");
+    assert!(out.guard.is_none());
+    assert_eq!(out.elements[0].tuple, [TheoryTermView::Symbol("x".into())]);
+
+    let sum = &views[1];
+    assert_eq!(sum.name(), "sum");
+    assert_eq!(sum.guard, Some(("<=".into(), TheoryTermView::Number(3))));
+    assert_eq!(sum.elements.len(), 2);
+    assert_eq!(
+        sum.elements[0].tuple,
+        [
+            TheoryTermView::Function(
+                "*".into(),
+                vec![
+                    TheoryTermView::Number(2),
+                    TheoryTermView::Symbol("x".into())
+                ]
+            ),
+            TheoryTermView::Set(vec![TheoryTermView::List(vec![TheoryTermView::Symbol(
+                "x".into()
+            )])])
+        ]
+    );
+    assert!(sum.elements[0].condition.is_empty());
+    assert_eq!(
+        sum.elements[1].tuple,
+        [TheoryTermView::Tuple(vec![
+            TheoryTermView::Symbol("x".into()),
+            TheoryTermView::Number(3)
+        ])]
+    );
+    assert_eq!(sum.elements[1].condition.len(), 1);
+}
+
+#[test]
+fn ground_program_recorder() {
+    let recorder = observer::GroundProgramRecorder::new();
+    let mut ctl = control(vec!["0".into(), "--opt-mode=optN".into()]).unwrap();
+    ctl.register_observer(Box::new(recorder.clone()), false)
+        .unwrap();
+    ctl.add(
+        "base",
+        &[],
+        "#theory t { term { }; &a/0 : term, any }.
+         p(1). {q(1..3)}. r :- q(X), not q(X+1), X < 3. :- #count { X : q(X) } > 2.
+         #minimize { 1,X : X = 1..3, not q(X) }. #external e. s :- e. &a { x : q(1) }.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    let (_, expected) = common::optimal_models(ctl);
+    assert_eq!(expected.len(), 3);
+
+    let steps = recorder.steps();
+    assert_eq!(steps.len(), 1);
+    assert!(steps[0]
+        .statements
+        .iter()
+        .any(|statement| matches!(statement, observer::GroundStatement::Minimize { .. })));
+
+    let mut replayed = control(vec!["0".into(), "--opt-mode=optN".into()]).unwrap();
+    recorder.replay(&mut replayed.backend().unwrap()).unwrap();
+    assert_eq!(replayed.theory_atoms().unwrap().size().unwrap(), 1);
+    assert_eq!(common::optimal_models(replayed).1, expected);
+
+    let mut replayed = control(vec!["0".into(), "--opt-mode=optN".into()]).unwrap();
+    steps[0].replay(&mut replayed.backend().unwrap()).unwrap();
+    let e = replayed
+        .symbolic_atoms()
+        .unwrap()
+        .iter()
+        .unwrap()
+        .find(|x| x.symbol().unwrap().to_string() == "e")
+        .unwrap()
+        .literal()
+        .unwrap();
+    replayed.assign_external(e, TruthValue::True).unwrap();
+    let (_, models) = common::optimal_models(replayed);
+    assert_eq!(models.len(), 3);
+    assert!(models
+        .iter()
+        .all(|model| model.contains(&"e".to_owned()) && model.contains(&"s".to_owned())));
+
+    recorder.clear();
+    assert!(recorder.steps().is_empty());
+
+    let mut ctl = control(vec![]).unwrap();
+    ctl.register_observer(Box::new(recorder.clone()), false)
+        .unwrap();
+    ctl.add("base", &[], "{ c }. #show t : c.").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    let mut replayed = control(vec![]).unwrap();
+    assert!(recorder.replay(&mut replayed.backend().unwrap()).is_err());
+    assert_eq!(replayed.symbolic_atoms().unwrap().size().unwrap(), 0);
+    recorder.clear();
+
+    let mut ctl = control(vec![]).unwrap();
+    ctl.register_observer(Box::new(recorder.clone()), false)
+        .unwrap();
+    recorder.set_enabled(false);
+    assert!(!recorder.is_enabled());
+    ctl.add("base", &[], "p(1).").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    assert!(recorder.steps().is_empty());
+}
+#[derive(Clone, Default)]
+struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+#[test]
+fn aspif_writer() {
+    let buffer = SharedBuffer::default();
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    ctl.register_observer(
+        Box::new(observer::aspif::AspifWriter::new(buffer.clone())),
+        false,
+    )
+    .unwrap();
+    ctl.add(
+        "base",
+        &[],
+        "#theory t { term { }; &a/0 : term, {<}, term, any }.
+         p(\"a b\"). {q(1..3)}. :- #count { X : q(X) } > 2. #minimize { 1,X : q(X) }.
+         #external e. #heuristic q(1). [1,level] #project q/1. &a { x : q(1) } < 2.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    let mut models = 0;
+    while handle.model().unwrap().is_some() {
+        models += 1;
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+
+    let aspif = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert!(aspif.starts_with("asp 1 0 0"));
+    assert!(aspif.ends_with("0\n"));
+    assert!(aspif.contains("4 8 p(\"a b\") 0\n"));
+
+    let programs = observer::aspif::parse(&aspif).unwrap();
+    assert_eq!(programs.len(), 1);
+    let statements = &programs[0].statements;
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::WeightRule { .. })));
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::Minimize { .. })));
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::External { .. })));
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::Heuristic { .. })));
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::Project { .. })));
+    assert!(statements
+        .iter()
+        .any(|s| matches!(s, observer::GroundStatement::TheoryAtomWithGuard { .. })));
+
+    let mut replayed = control(vec!["0".into(), "--project".into()]).unwrap();
+    observer::aspif::read(&aspif, &mut replayed.backend().unwrap()).unwrap();
+    assert_eq!(replayed.theory_atoms().unwrap().size().unwrap(), 1);
+    let mut handle = replayed.solve(SolveMode::YIELD, &[]).unwrap();
+    let mut replayed_models = 0;
+    while let Some(model) = handle.model().unwrap() {
+        let symbols = model.symbols(ShowType::SHOWN).unwrap();
+        assert!(symbols
+            .iter()
+            .any(|symbol| symbol.to_string() == "p(\"a b\")"));
+        replayed_models += 1;
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+    assert_eq!(replayed_models, models);
+}
+#[test]
+fn aspif_parse() {
+    let programs = observer::aspif::parse(
+        "asp 1 0 0 incremental\n1 1 2 1 2 0 0\n4 1 a 1 1\n10 a comment\n0\n\
+         1 0 1 3 1 1 2 1 1 -2 1\n4 3 f() 0\n0\n",
+    )
+    .unwrap();
+    assert_eq!(programs.len(), 2);
+    assert!(matches!(
+        &programs[0].statements[..],
+        [
+            observer::GroundStatement::Rule { choice: true, .. },
+            observer::GroundStatement::OutputAtom { .. }
+        ]
+    ));
+    assert!(matches!(
+        &programs[1].statements[..],
+        [
+            observer::GroundStatement::WeightRule {
+                choice: false,
+                lower_bound: 1,
+                ..
+            },
+            observer::GroundStatement::OutputAtom { .. }
+        ]
+    ));
+    assert!(observer::aspif::parse("1 0 0 0 0\n").is_err());
+    assert!(observer::aspif::parse("asp 1 0 0\n1 0 1\n").is_err());
+
+    let aspif = "asp 1 0 0\n1 1 1 1 0 0\n4 1 t 1 -1\n0\n";
+    let programs = observer::aspif::parse(aspif).unwrap();
+    assert!(matches!(
+        &programs[0].statements[..],
+        [_, observer::GroundStatement::OutputTerm { .. }]
+    ));
+    let mut ctl = control(vec![]).unwrap();
+    assert!(observer::aspif::read(aspif, &mut ctl.backend().unwrap()).is_err());
+}
+#[test]
+fn ground_program_printer() {
+    let buffer = SharedBuffer::default();
+    let mut ctl = control(vec![]).unwrap();
+    ctl.register_observer(
+        Box::new(observer::GroundProgramPrinter::new(buffer.clone())),
+        false,
+    )
+    .unwrap();
+    ctl.add(
+        "base",
+        &[],
+        "p(1). {q(1..2)}. r :- q(1), not q(2). :- #count { X : q(X) } > 1.
+         #minimize { 1,X : q(X) }. #external e.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    ctl.solve(SolveMode::empty(), &[]).unwrap().close().unwrap();
+
+    let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    for expected in [
+        "p(1).",
+        "{q(1)}.",
+        "{q(2)}.",
+        "r :- not q(2), q(1).",
+        "#minimize { 1@0,q(1): q(1); 1@0,q(2): q(2) }.",
+        "#external e. [false]",
+    ] {
+        assert!(lines.contains(&expected), "missing {expected} in {text}");
+    }
+    assert!(lines
+        .iter()
+        .any(|line| line.ends_with(":- 2 <= #sum { 1,q(1): q(1); 1,q(2): q(2) }.")));
+
+    let mut printer = observer::GroundProgramPrinter::new(vec![]);
+    assert!(printer.rule(false, &[], &[]));
+    assert!(printer.end_step());
+    assert_eq!(String::from_utf8(printer.into_inner()).unwrap(), ":- .\n");
+}
+#[test]
+fn grounding_cache() {
+    fn ground_and_solve(
+        cache: &cache::GroundingCache,
+        facts: &FactBase,
+    ) -> (bool, Vec<Vec<String>>) {
+        let arguments = vec!["0".to_owned()];
+        let mut ctl = control(arguments.clone()).unwrap();
+        let mut cached = cache.control(&mut ctl, &arguments);
+        cached.add("base", &[], "{p(X)} :- q(X). r(X) :- p(X), not q(X+1).");
+        cached.add("step", &["t"], "s(t).");
+        cached.add_facts(facts);
+        let hit = cached
+            .ground(&[
+                Part::new("base", vec![]).unwrap(),
+                Part::new("step", vec![Symbol::create_number(1)]).unwrap(),
+            ])
+            .unwrap();
+        (hit, common::models(ctl).1)
+    }
+
+    let cache = cache::GroundingCache::new();
+    let mut facts = FactBase::new();
+    for i in 1..=2 {
+        facts
+            .insert(&Symbol::create_function("q", &[Symbol::create_number(i)], true).unwrap())
+            .unwrap();
+    }
+    let (hit, expected) = ground_and_solve(&cache, &facts);
+    assert!(!hit);
+    assert_eq!(expected.len(), 4);
+    assert_eq!(cache.len(), 1);
+
+    let (hit, models) = ground_and_solve(&cache, &facts);
+    assert!(hit);
+    assert_eq!(models, expected);
+
+    facts
+        .insert(&Symbol::create_function("q", &[Symbol::create_number(3)], true).unwrap())
+        .unwrap();
+    let (hit, models) = ground_and_solve(&cache, &facts);
+    assert!(!hit);
+    assert_eq!(models.len(), 8);
+    assert_eq!(cache.len(), 2);
+
+    // constants of the control arguments are part of the key
+    let ground_constant = |program: &str, n: u32| {
+        let arguments = vec!["0".to_owned(), "-c".to_owned(), format!("n={n}")];
+        let mut ctl = control(arguments.clone()).unwrap();
+        let mut cached = cache.control(&mut ctl, &arguments);
+        cached.add("base", &[], program);
+        let hit = cached
+            .ground(&[Part::new("base", vec![]).unwrap()])
+            .unwrap();
+        (hit, common::models(ctl).1)
+    };
+    for (n, expected_hit) in [(3, false), (5, false), (3, true)] {
+        let (hit, models) = ground_constant("p(n).", n);
+        assert_eq!(hit, expected_hit);
+        assert_eq!(models, [[format!("p({n})")]]);
+    }
+    assert_eq!(cache.len(), 4);
+
+    // shown terms cannot be replayed and are not cached
+    let program = "{ c }. #show t(n) : c.";
+    let (hit, expected) = ground_constant(program, 1);
+    assert!(!hit);
+    assert_eq!(expected, [vec![], vec!["c".to_owned(), "t(1)".to_owned()]]);
+    assert_eq!(cache.len(), 4);
+    assert_eq!(ground_constant(program, 1), (false, expected));
+
+    let mut ctl = control(vec![]).unwrap();
+    ctl.add("base", &[], "p(1).").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    let mut cached = cache.control(&mut ctl, &[]);
+    cached.add("base", &[], "{p(X)} :- q(X). r(X) :- p(X), not q(X+1).");
+    cached.add_facts(&facts);
+    assert!(cached
+        .ground(&[Part::new("base", vec![]).unwrap()])
+        .is_err());
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+#[test]
+fn incremental_solver() {
+    #[derive(Default)]
+    struct ModelCounter {
+        models: Vec<Vec<String>>,
+    }
+    impl SolveEventHandler for ModelCounter {
+        fn on_solve_event(&mut self, event: SolveEvent, _goon: &mut bool) -> bool {
+            if let SolveEvent::Model(model) = event {
+                let symbols = model.symbols(ShowType::SHOWN).unwrap();
+                self.models
+                    .push(symbols.iter().map(|symbol| symbol.to_string()).collect());
+            }
+            true
+        }
+    }
+    fn control_with_program() -> Control {
+        let mut ctl = control(vec![]).unwrap();
+        ctl.add("base", &[], "pos(0).").unwrap();
+        ctl.add("step", &["t"], "pos(t) :- pos(t-1).").unwrap();
+        ctl.add(
+            "check",
+            &["t"],
+            "#external query(t). goal(t) :- pos(t), t >= 3. :- query(t), not goal(t).",
+        )
+        .unwrap();
+        ctl
+    }
+
+    let mut handler = ModelCounter::default();
+    let mut result = incremental::IncrementalSolver::new()
+        .solve(control_with_program(), &mut handler)
+        .unwrap();
+    assert_eq!(result.steps, 4);
+    assert!(result.result.contains(SolveResult::SATISFIABLE));
+    assert_eq!(handler.models.len(), 1);
+    assert!(handler.models[0].contains(&"goal(3)".to_owned()));
+    let query = Symbol::create_function("query", &[Symbol::create_number(3)], true).unwrap();
+    let externals = result.control.externals();
+    assert_eq!(externals.value(&query).unwrap(), Some(TruthValue::True));
+
+    let mut handler = ModelCounter::default();
+    let result = incremental::IncrementalSolver::new()
+        .imax(Some(2))
+        .solve(control_with_program(), &mut handler)
+        .unwrap();
+    assert_eq!(result.steps, 2);
+    assert!(result.result.contains(SolveResult::UNSATISFIABLE));
+    assert!(handler.models.is_empty());
+
+    let mut handler = ModelCounter::default();
+    let result = incremental::IncrementalSolver::new()
+        .imin(6)
+        .solve(control_with_program(), &mut handler)
+        .unwrap();
+    assert_eq!(result.steps, 6);
+    assert_eq!(handler.models.len(), 3);
+
+    let mut handler = ModelCounter::default();
+    let result = incremental::IncrementalSolver::new()
+        .istop(incremental::StopCriterion::Unsatisfiable)
+        .solve(control_with_program(), &mut handler)
+        .unwrap();
+    assert_eq!(result.steps, 1);
+
+    let mut ctl = control(vec![]).unwrap();
+    ctl.add("init", &[], "pos(0).").unwrap();
+    ctl.add("next", &["t"], "pos(t) :- pos(t-1).").unwrap();
+    ctl.add("goal", &["t"], "#external query(t). :- query(t), t < 1.")
+        .unwrap();
+    let result = incremental::IncrementalSolver::new()
+        .base("init")
+        .step("next")
+        .check("goal")
+        .solve(ctl, &mut handler)
+        .unwrap();
+    assert_eq!(result.steps, 2);
+}
+#[test]
+fn externals() {
+    let mut ctl = control(vec![]).unwrap();
+    ctl.add(
+        "base",
+        &[],
+        "#external a(1..2). #external b. [true] p :- a(1). q :- b. #show p/0. #show q/0.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let a1 = Symbol::create_function("a", &[Symbol::create_number(1)], true).unwrap();
+    let b = Symbol::create_id("b", true).unwrap();
+    let c = Symbol::create_id("c", true).unwrap();
+    let a = Signature::new("a", 1, true).unwrap();
+
+    let mut externals = ctl.externals();
+    assert_eq!(externals.list(a).unwrap().len(), 2);
+    assert_eq!(externals.value(&a1).unwrap(), None);
+    assert_eq!(externals.value(&b).unwrap(), None);
+    assert!(externals.value(&c).is_err());
+    externals.assign(&a1, TruthValue::True).unwrap();
+    externals.assign(&b, TruthValue::False).unwrap();
+    assert!(externals.assign(&c, TruthValue::True).is_err());
+    let (mut ctl, models) = common::models(ctl);
+    assert_eq!(models, [["p"]]);
+
+    let mut externals = ctl.externals();
+    assert_eq!(externals.value(&a1).unwrap(), Some(TruthValue::True));
+    assert_eq!(externals.value(&b).unwrap(), Some(TruthValue::False));
+    externals.release(&a1).unwrap();
+    assert!(externals.value(&a1).is_err());
+    assert_eq!(externals.list(a).unwrap().len(), 1);
+    assert!(externals.release(&a1).is_err());
+    let literal = ctl
+        .symbolic_atoms()
+        .unwrap()
+        .iter()
+        .unwrap()
+        .find(|atom| atom.symbol().unwrap() == b)
+        .unwrap()
+        .literal()
+        .unwrap();
+    ctl.assign_external(literal.negate(), TruthValue::False)
+        .unwrap();
+    assert_eq!(ctl.externals().value(&b).unwrap(), Some(TruthValue::True));
+    let (mut ctl, models) = common::models(ctl);
+    assert_eq!(models, [["q"]]);
+
+    // declaring the external again resets its value
+    ctl.add("step", &[], "#external b. [false]").unwrap();
+    ctl.ground(&[Part::new("step", vec![]).unwrap()]).unwrap();
+    assert_eq!(ctl.externals().value(&b).unwrap(), None);
+    let (_, models) = common::models(ctl);
+    assert_eq!(models, [Vec::<String>::new()]);
+}
 fn test_statement(stmt: &Statement, string: &str) {
     let string2 = format!("{}", stmt);
     assert_eq!(string2, string);
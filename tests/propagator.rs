@@ -3,6 +3,8 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use test_case::test_case;
 
+mod common;
+
 #[derive(Debug)]
 struct StateT {
     // assignment of pigeons to holes
@@ -436,8 +438,8 @@ fn mode_propagator() {
     );
 }
 
-use std::collections::HashSet;
-use std::sync::{Condvar, Mutex};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Condvar, Mutex};
 struct TestAddWatch {
     propagated: HashSet<SolverLiteral>,
     a: Option<SolverLiteral>,
@@ -678,3 +680,480 @@ fn add_clause(clause_type: ClauseType, m1: usize, m2: usize) {
     let (models, _) = solve(ctl).unwrap();
     assert_eq!(models.len(), m2);
 }
+
+struct TestForbid {
+    symbol: Symbol,
+}
+impl Propagator for TestForbid {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let atom = init
+            .symbolic_atoms()
+            .unwrap()
+            .iter()
+            .unwrap()
+            .find(|x| x.symbol().unwrap() == self.symbol)
+            .unwrap()
+            .literal()
+            .unwrap();
+        let lit = init.solver_literal(atom).unwrap();
+        init.add_clause(&[lit.negate()]).unwrap()
+    }
+}
+struct TestCountChecks {
+    count: std::sync::Arc<Mutex<usize>>,
+}
+impl Propagator for TestCountChecks {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        init.set_check_mode(PropagatorCheckMode::Total);
+        true
+    }
+    fn check(&mut self, _ctl: &mut PropagateControl) -> bool {
+        *self.count.lock().unwrap() += 1;
+        true
+    }
+}
+
+#[test]
+fn multiple_propagators() {
+    let count = std::sync::Arc::new(Mutex::new(0));
+    let ctrl_ctx = CtrlCtx {
+        non: defaults::Non,
+        propagator: defaults::Non,
+    };
+    let mut ctl = control_with_context(vec!["0".into()], ctrl_ctx).unwrap();
+    let symbol = Symbol::create_function("p", &[Symbol::create_number(1)], true).unwrap();
+    ctl.add_propagator(TestForbid { symbol }, PropagatorCheckMode::None, false)
+        .unwrap();
+    ctl.add_propagator(
+        TestMode { lits: vec![] },
+        PropagatorCheckMode::Fixpoint,
+        false,
+    )
+    .unwrap();
+    ctl.add_propagator(
+        TestCountChecks {
+            count: count.clone(),
+        },
+        PropagatorCheckMode::None,
+        true,
+    )
+    .unwrap();
+
+    ctl.add("base", &[], "{p(1..9)}.")
+        .expect("Failed to add a logic program.");
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+
+    let (models, _) = solve(ctl).unwrap();
+    assert_eq!(
+        models,
+        [["p(2)", "p(3)", "p(4)", "p(5)", "p(6)", "p(7)", "p(8)", "p(9)"]]
+    );
+    assert_eq!(*count.lock().unwrap(), 0);
+}
+
+struct TestCountRules {
+    count: std::sync::Arc<Mutex<usize>>,
+}
+impl GroundProgramObserver for TestCountRules {
+    fn rule(&mut self, _choice: bool, _head: &[Atom], _body: &[SolverLiteral]) -> bool {
+        *self.count.lock().unwrap() += 1;
+        true
+    }
+}
+fn forbid(ctl: &mut Control, symbol: Symbol) -> Result<(), ClingoError> {
+    ctl.register_propagator(Box::new(TestForbid { symbol }))
+}
+
+#[test]
+fn register_at_runtime() {
+    let count = std::sync::Arc::new(Mutex::new(0));
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let symbol = Symbol::create_function("p", &[Symbol::create_number(1)], true).unwrap();
+    forbid(&mut ctl, symbol).unwrap();
+    ctl.register_observer(
+        Box::new(TestCountRules {
+            count: count.clone(),
+        }),
+        false,
+    )
+    .unwrap();
+
+    ctl.add("base", &[], "{p(1..2)}.")
+        .expect("Failed to add a logic program.");
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+    assert_eq!(*count.lock().unwrap(), 2);
+
+    let mut handle = ctl
+        .solve(SolveMode::YIELD, &[])
+        .expect("Failed to retrieve solve handle.");
+    let mut models = vec![];
+    while let Some(model) = handle.model().unwrap() {
+        models.push(string_model(model));
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+    assert_eq!(models, [vec![], vec!["p(2)"]]);
+}
+
+struct TestAtMostOne {
+    lits: Vec<SolverLiteral>,
+}
+impl propagator::ThreadState for TestAtMostOne {
+    fn init(init: &mut PropagateInit, thread_id: u32) -> Result<Self, ClingoError> {
+        let mut lits = vec![];
+        let sig = Signature::new("p", 1, true)?;
+        for atom in init.symbolic_atoms()?.iter_with_signature(sig)? {
+            lits.push(init.solver_literal(atom.literal()?)?);
+        }
+        for lit in &lits {
+            init.add_watch_to_thread(*lit, thread_id)?;
+        }
+        Ok(TestAtMostOne { lits })
+    }
+    fn propagate(&mut self, ctl: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        for lit in changes {
+            for other in self.lits.iter().filter(|other| *other != lit) {
+                let clause = [lit.negate(), other.negate()];
+                if !ctl.add_clause(&clause, ClauseType::Learnt).unwrap() {
+                    return true;
+                }
+                if !ctl.propagate().unwrap() {
+                    return true;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[test]
+fn thread_local_propagator() {
+    let ctrl_ctx = CtrlCtx {
+        non: defaults::Non,
+        propagator: propagator::ThreadLocalPropagator::<TestAtMostOne>::new(),
+    };
+    let mut ctl =
+        control_with_context(vec!["0".into(), "--parallel-mode=2".into()], ctrl_ctx).unwrap();
+
+    ctl.add("base", &[], "{p(1..3)}.")
+        .expect("Failed to add a logic program.");
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+
+    let (mut models, _) = solve(ctl).unwrap();
+    models.sort();
+    assert_eq!(models, [vec![], vec!["p(1)"], vec!["p(2)"], vec!["p(3)"]]);
+}
+
+struct TestAtMostTwo {
+    lits: Vec<SolverLiteral>,
+    assigned: propagator::Trail<Vec<SolverLiteral>>,
+}
+impl Propagator for TestAtMostTwo {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let sig = Signature::new("p", 1, true).unwrap();
+        for atom in init
+            .symbolic_atoms()
+            .unwrap()
+            .iter_with_signature(sig)
+            .unwrap()
+        {
+            self.lits
+                .push(init.solver_literal(atom.literal().unwrap()).unwrap());
+        }
+        for lit in &self.lits {
+            init.add_watch(*lit).unwrap();
+        }
+        true
+    }
+    fn propagate(&mut self, ctl: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        let ass = ctl.assignment().unwrap();
+        for lit in changes {
+            self.assigned.modify(
+                ass,
+                |assigned| assigned.push(*lit),
+                |assigned| {
+                    assigned.pop();
+                },
+            );
+        }
+        if self.assigned.get().len() > 2 {
+            let clause: Vec<SolverLiteral> =
+                self.assigned.get().iter().map(|lit| lit.negate()).collect();
+            if !ctl.add_clause(&clause, ClauseType::Learnt).unwrap() {
+                return true;
+            }
+            if !ctl.propagate().unwrap() {
+                return true;
+            }
+        }
+        true
+    }
+    fn undo(&mut self, ctl: &mut PropagateControl, _changes: &[SolverLiteral]) {
+        self.assigned.undo(ctl);
+    }
+}
+
+#[test]
+fn trail_propagator() {
+    let ctrl_ctx = CtrlCtx {
+        non: defaults::Non,
+        propagator: TestAtMostTwo {
+            lits: vec![],
+            assigned: propagator::Trail::default(),
+        },
+    };
+    let mut ctl = control_with_context(vec!["0".into()], ctrl_ctx).unwrap();
+
+    ctl.add("base", &[], "{p(1..4)}.")
+        .expect("Failed to add a logic program.");
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+
+    let (models, _) = solve(ctl).unwrap();
+    assert_eq!(models.len(), 11);
+    assert!(models.iter().all(|model| model.len() <= 2));
+}
+
+struct TestSymbolMap {
+    forbidden: Symbol,
+    map: propagator::SymbolMap,
+}
+impl Propagator for TestSymbolMap {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let p = Signature::new("p", 1, true).unwrap();
+        let q = Signature::new("q", 0, true).unwrap();
+        self.map = init.watch_symbols(&[p, q]).unwrap();
+        assert_eq!(self.map.len(), 4);
+        let lit = self.map.literal(self.forbidden).unwrap();
+        assert!(self.map.symbols(lit).contains(&self.forbidden));
+        true
+    }
+    fn propagate(&mut self, ctl: &mut PropagateControl, changes: &[SolverLiteral]) -> bool {
+        for lit in changes {
+            if self.map.symbols(*lit).contains(&self.forbidden)
+                && !ctl.add_clause(&[lit.negate()], ClauseType::Learnt).unwrap()
+            {
+                return true;
+            }
+        }
+        true
+    }
+}
+
+#[test]
+fn symbol_map_propagator() {
+    let forbidden = Symbol::create_function("p", &[Symbol::create_number(2)], true).unwrap();
+    let ctrl_ctx = CtrlCtx {
+        non: defaults::Non,
+        propagator: TestSymbolMap {
+            forbidden,
+            map: propagator::SymbolMap::new(),
+        },
+    };
+    let mut ctl = control_with_context(vec!["0".into()], ctrl_ctx).unwrap();
+
+    ctl.add("base", &[], "{p(1..3)}. q.")
+        .expect("Failed to add a logic program.");
+
+    let part = Part::new("base", vec![]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+
+    let (models, _) = solve(ctl).unwrap();
+    assert_eq!(models.len(), 4);
+    assert!(models
+        .iter()
+        .all(|model| model.contains(&"q".to_string()) && !model.contains(&"p(2)".to_string())));
+}
+
+struct TestPigeonClauses {
+    // solver literal -> hole of the placement
+    holes: HashMap<SolverLiteral, i32>,
+    // hole -> pigeon placements assigned to the hole
+    placed: propagator::Trail<HashMap<i32, SolverLiteral>>,
+}
+impl propagator::ClauseGenerator for TestPigeonClauses {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let sig = Signature::new("place", 2, true).unwrap();
+        let map = init.watch_symbols(&[sig]).unwrap();
+        for (symbol, lit) in map.iter() {
+            self.holes.insert(lit, get_arg(&symbol, 1).unwrap());
+        }
+        true
+    }
+    fn propagate(
+        &mut self,
+        ctl: &PropagateControl,
+        changes: &[SolverLiteral],
+    ) -> propagator::Propagation {
+        let ass = ctl.assignment().unwrap();
+        for lit in changes {
+            let hole = self.holes[lit];
+            if let Some(other) = self.placed.get().get(&hole) {
+                let clause = propagator::Clause::learnt(vec![lit.negate(), other.negate()]);
+                return propagator::Propagation::Conflict(clause);
+            }
+            let lit = *lit;
+            self.placed.modify(
+                ass,
+                |placed| placed.insert(hole, lit),
+                move |placed| {
+                    placed.remove(&hole);
+                },
+            );
+        }
+        propagator::Propagation::default()
+    }
+    fn undo(&mut self, ctl: &PropagateControl, _changes: &[SolverLiteral]) {
+        self.placed.undo(ctl);
+    }
+}
+
+#[test_case(2, 2, 2; "sat")]
+#[test_case(5, 6, 0; "unsat")]
+fn lazy_clause_propagator(holes: i32, pigeons: i32, number_of_models: usize) {
+    let ctrl_ctx = CtrlCtx {
+        non: defaults::Non,
+        propagator: propagator::LazyClausePropagator::new(TestPigeonClauses {
+            holes: HashMap::new(),
+            placed: propagator::Trail::default(),
+        }),
+    };
+    let mut ctl = control_with_context(vec!["0".into()], ctrl_ctx).unwrap();
+
+    let h = Symbol::create_number(holes);
+    let p = Symbol::create_number(pigeons);
+    ctl.add(
+        "pigeon",
+        &["h", "p"],
+        "1 { place(P,H) : H = 1..h } 1 :- P = 1..p.",
+    )
+    .expect("Failed to add a logic program.");
+
+    let part = Part::new("pigeon", vec![h, p]).unwrap();
+    let parts = vec![part];
+    ctl.ground(&parts)
+        .expect("Failed to ground a logic program.");
+
+    let (models, _) = solve(ctl).unwrap();
+    assert_eq!(models.len(), number_of_models);
+}
+
+#[test]
+fn heuristics() {
+    let p = Signature::new("p", 1, true).unwrap();
+    let q = Signature::new("q", 1, true).unwrap();
+
+    let mut ctl = control(vec!["1".into()]).unwrap();
+    ctl.add_heuristic(
+        propagator::SignHeuristic::new()
+            .prefer(p, true)
+            .prefer(q, false),
+    )
+    .unwrap();
+    ctl.add("base", &[], "{p(1..3)}. {q(1..3)}.").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    assert_eq!(common::models(ctl).1, [["p(1)", "p(2)", "p(3)"]]);
+
+    let scores = [(3, 5), (1, 1)].map(|(arg, score)| {
+        let symbol = Symbol::create_function("p", &[Symbol::create_number(arg)], true).unwrap();
+        (symbol, score)
+    });
+    let mut ctl = control(vec!["1".into()]).unwrap();
+    ctl.add_heuristic(propagator::PriorityHeuristic::new(scores))
+        .unwrap();
+    ctl.add("base", &[], "1 {p(1..3)} 1.").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    assert_eq!(common::models(ctl).1, [["p(3)"]]);
+
+    let decisions = Arc::new(Mutex::new(vec![]));
+    let mut ctl = control(vec!["1".into()]).unwrap();
+    ctl.add_heuristic(RecordingHeuristic {
+        heuristic: propagator::AssignmentActivityHeuristic::new(&[p]).with_decay(0.5),
+        symbols: propagator::SymbolMap::new(),
+        decisions: decisions.clone(),
+    })
+    .unwrap();
+    ctl.add("base", &[], "{p(1..3)}.").unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+    // without activity the atoms are decided in the order of their solver literals
+    let (ctl, models) = common::models(ctl);
+    assert_eq!(models, [["p(1)", "p(2)", "p(3)"]]);
+    assert_eq!(
+        *decisions.lock().unwrap(),
+        [
+            ("p(1)".to_owned(), vec![0.0, 0.0, 0.0]),
+            ("p(2)".to_owned(), vec![1.0, 0.0, 0.0]),
+            ("p(3)".to_owned(), vec![1.0, 2.0, 0.0]),
+        ]
+    );
+
+    // the activities are kept and the atoms assigned last are decided first
+    decisions.lock().unwrap().clear();
+    let (_, models) = common::models(ctl);
+    assert_eq!(models, [["p(1)", "p(2)", "p(3)"]]);
+    let decisions = decisions.lock().unwrap();
+    let order: Vec<&str> = decisions
+        .iter()
+        .map(|(symbol, _)| symbol.as_str())
+        .collect();
+    assert_eq!(order, ["p(3)", "p(2)", "p(1)"]);
+    assert_eq!(decisions[0].1, [1.0, 2.0, 4.0]);
+}
+
+/// The decided atoms with the activities of `p(1..3)` before each decision.
+type Decisions = Arc<Mutex<Vec<(String, Vec<f64>)>>>;
+
+/// Records the decisions of a heuristic.
+struct RecordingHeuristic {
+    heuristic: propagator::AssignmentActivityHeuristic,
+    symbols: propagator::SymbolMap,
+    decisions: Decisions,
+}
+impl propagator::Heuristic for RecordingHeuristic {
+    fn init(&mut self, init: &mut PropagateInit) -> bool {
+        let p = Signature::new("p", 1, true).unwrap();
+        self.symbols = init.map_symbols(&[p]).unwrap();
+        self.heuristic.init(init)
+    }
+    fn propagate(&mut self, control: &PropagateControl, changes: &[SolverLiteral]) {
+        self.heuristic.propagate(control, changes)
+    }
+    fn undo(&mut self, control: &PropagateControl, changes: &[SolverLiteral]) {
+        self.heuristic.undo(control, changes)
+    }
+    fn decide(
+        &mut self,
+        thread_id: u32,
+        assignment: &Assignment,
+        fallback: SolverLiteral,
+    ) -> Option<SolverLiteral> {
+        let activities = (1..=3)
+            .map(|arg| {
+                let symbol = Symbol::create_function("p", &[Symbol::create_number(arg)], true);
+                let literal = self.symbols.literal(symbol.unwrap()).unwrap();
+                self.heuristic.activity(literal)
+            })
+            .collect();
+        let decision = self.heuristic.decide(thread_id, assignment, fallback)?;
+        let symbol = self.symbols.symbols(decision)[0].to_string();
+        self.decisions.lock().unwrap().push((symbol, activities));
+        Some(decision)
+    }
+}
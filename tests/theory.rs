@@ -0,0 +1,321 @@
+use clingo::theory::difference_logic::DifferenceLogic;
+#[cfg(feature = "dynamic")]
+use clingo::theory::dynamic::DynamicTheory;
+use clingo::theory::linear::LinearConstraints;
+use clingo::theory::{Theory, TheoryRunner, TheoryValue};
+use clingo::*;
+use std::collections::HashMap;
+
+fn value(values: &HashMap<String, i64>, name: &str) -> i64 {
+    values[name]
+}
+
+#[test]
+fn difference_logic() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut dl = DifferenceLogic::new();
+    assert!(dl.register(&mut ctl));
+
+    ctl.add(
+        "base",
+        &[],
+        "&diff { 0 - a } <= 0. \
+         &diff { a - b } <= -2. \
+         &diff { b - c } <= -3. \
+         { p; q }. \
+         &diff { c - a } <= 4 :- p. \
+         &diff { c - a } <= 7 :- q.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    let mut models = vec![];
+    while let Some(model) = handle.model().unwrap() {
+        let thread_id = model.thread_id().unwrap();
+        let values: HashMap<String, i64> = dl
+            .values(thread_id.get_integer())
+            .into_iter()
+            .map(|(symbol, value)| (symbol.to_string(), value))
+            .collect();
+        assert_eq!(values.len(), 3);
+        let (a, b, c) = (
+            value(&values, "a"),
+            value(&values, "b"),
+            value(&values, "c"),
+        );
+        assert!(a >= 0 && a - b <= -2 && b - c <= -3);
+        let mut atoms: Vec<String> = model
+            .symbols(ShowType::SHOWN)
+            .unwrap()
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect();
+        atoms.sort();
+        if atoms.contains(&"q".to_string()) {
+            assert!(c - a <= 7);
+        } else {
+            assert!(c - a > 7);
+        }
+        for (_, value) in dl.assignment(thread_id) {
+            assert!(matches!(value, TheoryValue::IntNumber(_)));
+        }
+        models.push(atoms);
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+    models.sort();
+    assert_eq!(models, [vec![], vec!["q"]]);
+}
+
+#[test]
+fn difference_logic_unsatisfiable() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut dl = DifferenceLogic::new();
+    assert!(dl.register(&mut ctl));
+
+    ctl.add(
+        "base",
+        &[],
+        "&diff { a - b } <= -1. \
+         &diff { b - a } <= -1.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    assert!(handle.model().unwrap().is_none());
+    assert!(handle.get().unwrap().contains(SolveResult::UNSATISFIABLE));
+    handle.close().unwrap();
+}
+
+#[test]
+fn linear_constraints() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut lc = LinearConstraints::new();
+    assert!(lc.register(&mut ctl));
+
+    ctl.add(
+        "base",
+        &[],
+        "&dom { 1..5 } = x. \
+         &dom { 1..5 } = y. \
+         &sum { x + y } = 6. \
+         &sum { x } != 4. \
+         { p }. \
+         &sum { 2*x; -y } >= 3 :- p. \
+         :- &sum { x } < 2.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    let mut models = vec![];
+    while let Some(model) = handle.model().unwrap() {
+        let thread_id = model.thread_id().unwrap();
+        let values: HashMap<String, i64> = lc
+            .values(thread_id.get_integer())
+            .into_iter()
+            .map(|(symbol, value)| (symbol.to_string(), value))
+            .collect();
+        assert_eq!(values.len(), 2);
+        for (_, value) in lc.assignment(thread_id) {
+            assert!(matches!(value, TheoryValue::IntNumber(_)));
+        }
+        let p = model
+            .symbols(ShowType::SHOWN)
+            .unwrap()
+            .iter()
+            .any(|symbol| symbol.to_string() == "p");
+        models.push((p, value(&values, "x"), value(&values, "y")));
+        handle.resume().unwrap();
+    }
+    handle.close().unwrap();
+    models.sort();
+    assert_eq!(models, [(false, 2, 4), (true, 3, 3), (true, 5, 1)]);
+}
+
+#[test]
+fn linear_constraints_unsatisfiable() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut lc = LinearConstraints::with_domain(0, 10);
+    assert!(lc.register(&mut ctl));
+
+    ctl.add(
+        "base",
+        &[],
+        "&sum { 3*x; 3*y } = 10. \
+         &sum { x } >= 0.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let mut handle = ctl.solve(SolveMode::YIELD, &[]).unwrap();
+    assert!(handle.model().unwrap().is_none());
+    assert!(handle.get().unwrap().contains(SolveResult::UNSATISFIABLE));
+    handle.close().unwrap();
+}
+
+#[derive(Default)]
+struct EventCounter {
+    models: Vec<Vec<String>>,
+    statistics: usize,
+    finished: bool,
+}
+impl SolveEventHandler for EventCounter {
+    fn on_solve_event(&mut self, event: SolveEvent, _goon: &mut bool) -> bool {
+        match event {
+            SolveEvent::Model(model) => {
+                let mut atoms: Vec<String> = model
+                    .symbols(ShowType::SHOWN | ShowType::THEORY)
+                    .unwrap()
+                    .iter()
+                    .map(|symbol| symbol.to_string())
+                    .collect();
+                atoms.sort();
+                self.models.push(atoms);
+            }
+            SolveEvent::Statistics { .. } => self.statistics += 1,
+            SolveEvent::Finish(_) => self.finished = true,
+            SolveEvent::Unsat => {}
+        }
+        true
+    }
+}
+
+#[test]
+fn theory_runner() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut runner = TheoryRunner::new(DifferenceLogic::new());
+    runner.register(&mut ctl).unwrap();
+    runner
+        .add(
+            &mut ctl,
+            "#show p/0. \
+             { p }. \
+             &diff { a - 0 } <= 0. \
+             &diff { 0 - a } <= 0. \
+             &diff { a - b } <= -2 :- p. \
+             &diff { b - a } <= 1.",
+        )
+        .unwrap();
+    runner
+        .ground(&mut ctl, &[Part::new("base", vec![]).unwrap()])
+        .unwrap();
+
+    let mut counter = EventCounter::default();
+    let mut handle = runner
+        .solve(ctl, SolveMode::empty(), &[], &mut counter)
+        .unwrap();
+    assert!(handle.get().unwrap().contains(SolveResult::SATISFIABLE));
+    handle.close().unwrap();
+
+    counter.models.sort();
+    assert_eq!(counter.models.len(), 1);
+    assert!(counter.models[0].contains(&"dl(a,0)".to_string()));
+    assert!(!counter.models[0].contains(&"p".to_string()));
+    assert!(counter.statistics > 0);
+    assert!(counter.finished);
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_theory_missing_library() {
+    let theory = unsafe { DynamicTheory::load("libclingo-missing-theory.so", "clingomissing") };
+    assert!(matches!(theory, Err(ClingoError::LibraryError(_))));
+}
+
+/// Compile the plugin in `tests/fixtures/theory_plugin.rs` to a shared library.
+#[cfg(feature = "dynamic")]
+fn theory_plugin() -> std::path::PathBuf {
+    use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+    let source = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/theory_plugin.rs"
+    );
+    let library = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join(format!("{DLL_PREFIX}clingo_test_theory{DLL_SUFFIX}"));
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let status = std::process::Command::new(rustc)
+        .args(["--crate-type", "cdylib", "--edition", "2021", "-o"])
+        .arg(&library)
+        .arg(source)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    library
+}
+
+#[cfg(feature = "dynamic")]
+#[derive(Default)]
+struct ThreadRecorder {
+    thread_id: Option<Id>,
+}
+#[cfg(feature = "dynamic")]
+impl SolveEventHandler for ThreadRecorder {
+    fn on_solve_event(&mut self, event: SolveEvent, _goon: &mut bool) -> bool {
+        if let SolveEvent::Model(model) = event {
+            self.thread_id = model.thread_id().ok();
+        }
+        true
+    }
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_theory() {
+    let mut theory = unsafe { DynamicTheory::load(theory_plugin(), "clingotest") }.unwrap();
+    assert!(theory.configure("offset", "40"));
+    assert!(!theory.configure("unknown", "0"));
+    assert!(!theory.configure("offset", "forty"));
+
+    let x = Symbol::create_id("x", true).unwrap();
+    let mut index = 0;
+    assert!(theory.lookup_symbol(x, &mut index));
+    assert_eq!(theory.get_symbol(index), x);
+
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut runner = TheoryRunner::new(theory);
+    runner.register(&mut ctl).unwrap();
+    runner.add(&mut ctl, "{ p }.").unwrap();
+    runner
+        .ground(&mut ctl, &[Part::new("base", vec![]).unwrap()])
+        .unwrap();
+
+    let mut recorder = ThreadRecorder::default();
+    let mut handle = runner
+        .solve(ctl, SolveMode::empty(), &[], &mut recorder)
+        .unwrap();
+    assert!(handle.get().unwrap().contains(SolveResult::SATISFIABLE));
+    handle.close().unwrap();
+
+    // the plugin has seen both models
+    let values: Vec<_> = runner
+        .theory()
+        .assignment(recorder.thread_id.unwrap())
+        .map(|(symbol, value)| (symbol.to_string(), value))
+        .collect();
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0].0, "x");
+    assert!(matches!(values[0].1, TheoryValue::IntNumber(42)));
+}
+
+#[test]
+fn linear_constraints_overflow() {
+    let mut ctl = control(vec!["0".into()]).unwrap();
+    let mut lc = LinearConstraints::new();
+    assert!(lc.register(&mut ctl));
+
+    ctl.add(
+        "base",
+        &[],
+        "&sum { 2147483647*2147483647*2147483647*x } <= 0.",
+    )
+    .unwrap();
+    ctl.ground(&[Part::new("base", vec![]).unwrap()]).unwrap();
+
+    let error = ctl
+        .solve(SolveMode::YIELD, &[])
+        .and_then(|mut handle| handle.get());
+    assert!(error.is_err());
+}